@@ -0,0 +1,61 @@
+//! A huge inward-facing backdrop that boils like a moving painting behind a foreground prop.
+//!
+//! The backdrop is a dense sphere hundreds of units across, viewed from inside. Displacement
+//! is measured in normalized device coordinates, so its wobble matches the foreground's on
+//! screen regardless of the mesh's scale.
+
+use bevy::prelude::*;
+use bevy_line_boil::{LineBoil, LineBoilPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(LineBoilPlugin)
+        .add_systems(Startup, setup)
+        .add_systems(Update, orbit_camera)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Boil moves vertices, so the backdrop needs enough of them for details to wobble.
+    let backdrop = Sphere::new(500.0).mesh().ico(6).expect("valid subdivision count");
+    commands.spawn((
+        Mesh3d(meshes.add(backdrop)),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: Color::srgb(0.55, 0.7, 0.9),
+            unlit: true,
+            // Viewed from inside.
+            cull_mode: None,
+            ..default()
+        })),
+        LineBoil::subtle().with_seed(7.0),
+    ));
+
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(1.0, 1.0, 1.0))),
+        MeshMaterial3d(materials.add(Color::srgb(0.9, 0.5, 0.3))),
+        LineBoil::aggressive(),
+    ));
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 1.5, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
+
+fn orbit_camera(time: Res<Time>, mut camera: Query<&mut Transform, With<Camera3d>>) {
+    for mut transform in &mut camera {
+        let angle = time.elapsed_secs() * 0.2;
+        *transform = Transform::from_xyz(angle.sin() * 4.0, 1.5, angle.cos() * 4.0)
+            .looking_at(Vec3::ZERO, Vec3::Y);
+    }
+}
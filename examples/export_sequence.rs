@@ -0,0 +1,121 @@
+//! Captures consecutive held frames of the boil to `boil_000.png`, `boil_001.png`, ...
+//!
+//! Run with an optional glTF path (relative to `assets/`) and frame count:
+//!
+//! ```sh
+//! cargo run --example export_sequence -- models/character.glb 8
+//! ```
+//!
+//! Every held frame is a different drawing, so the sequence loops cleanly. Turn it into a GIF
+//! with e.g. `ffmpeg -framerate 8 -i boil_%03d.png -loop 0 boil.gif`.
+
+use bevy::{
+    app::AppExit,
+    prelude::*,
+    render::view::screenshot::{Screenshot, ScreenshotCaptured, save_to_disk},
+};
+use bevy_line_boil::{LineBoil, LineBoilClock, LineBoilPlugin, LineBoilSettings};
+
+/// Frames rendered before the first capture, while assets load and pipelines compile.
+const WARM_UP_FRAMES: u32 = 60;
+/// Frames rendered after stepping the clock, so the new time is uploaded and drawn.
+const SETTLE_FRAMES: u32 = 2;
+
+#[derive(Resource)]
+struct Export {
+    frame: u32,
+    frames: u32,
+    wait: u32,
+    capturing: bool,
+}
+
+/// glTF file to boil, or `None` for a built-in torus.
+#[derive(Resource)]
+struct Model(Option<String>);
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let model = args.next();
+    let frames = args.next().and_then(|frames| frames.parse().ok()).unwrap_or(8);
+
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(LineBoilPlugin)
+        .insert_resource(Export {
+            frame: 0,
+            frames,
+            wait: WARM_UP_FRAMES,
+            capturing: false,
+        })
+        .insert_resource(Model(model))
+        .add_systems(Startup, setup)
+        .add_systems(Update, step_and_capture)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    model: Res<Model>,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    match &model.0 {
+        Some(path) => {
+            commands.spawn((
+                SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset(path.clone()))),
+                LineBoil::subtle(),
+            ));
+        }
+        None => {
+            commands.spawn((
+                Mesh3d(meshes.add(Torus::new(0.6, 1.0))),
+                MeshMaterial3d(materials.add(Color::srgb(0.9, 0.5, 0.3))),
+                LineBoil::subtle(),
+            ));
+        }
+    }
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 2.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+}
+
+fn step_and_capture(
+    mut commands: Commands,
+    mut export: ResMut<Export>,
+    mut clock: ResMut<LineBoilClock>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    if export.frame == export.frames {
+        exit.write(AppExit::Success);
+        return;
+    }
+
+    // Hold the boil on the frame being captured, however long the capture takes.
+    clock.set_held_frame(export.frame, LineBoilSettings::SUBTLE.frame_rate);
+
+    if export.capturing {
+        return;
+    }
+    if export.wait > 0 {
+        export.wait -= 1;
+        return;
+    }
+
+    export.capturing = true;
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(format!("boil_{:03}.png", export.frame)))
+        .observe(|_: On<ScreenshotCaptured>, mut export: ResMut<Export>| {
+            export.frame += 1;
+            export.wait = SETTLE_FRAMES;
+            export.capturing = false;
+        });
+}
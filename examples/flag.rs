@@ -0,0 +1,115 @@
+//! Waving two-layer flags: the top one is boiled with `sheet_thickness`, so its front and
+//! back displace together, while the bottom one shows both layers crawling through each other.
+
+use bevy::{
+    asset::RenderAssetUsages,
+    mesh::{Indices, PrimitiveTopology},
+    prelude::*,
+};
+use bevy_line_boil::{LineBoil, LineBoilPlugin};
+
+const COLUMNS: u32 = 48;
+const ROWS: u32 = 32;
+const SIZE: Vec2 = Vec2::new(3.0, 2.0);
+const THICKNESS: f32 = 0.03;
+
+#[derive(Component)]
+struct Flag;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(LineBoilPlugin)
+        .add_systems(Startup, setup)
+        .add_systems(Update, wave_flags)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let material = materials.add(Color::srgb(0.8, 0.2, 0.25));
+    for (height, sheet_thickness) in [(1.2, THICKNESS), (-1.2, 0.0)] {
+        commands.spawn((
+            Flag,
+            Mesh3d(meshes.add(flag_mesh(0.0))),
+            MeshMaterial3d(material.clone()),
+            Transform::from_xyz(0.0, height, 0.0),
+            LineBoil::aggressive().with_sheet_thickness(sheet_thickness),
+        ));
+    }
+
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(2.0, 4.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.5, 0.5, 5.5).looking_at(Vec3::new(SIZE.x * 0.5, 0.0, 0.0), Vec3::Y),
+    ));
+}
+
+fn wave_flags(
+    time: Res<Time>,
+    flags: Query<&Mesh3d, With<Flag>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for mesh in &flags {
+        let Some(mesh) = meshes.get_mut(&mesh.0) else {
+            continue;
+        };
+        let waved = flag_mesh(time.elapsed_secs());
+        for attribute in [Mesh::ATTRIBUTE_POSITION, Mesh::ATTRIBUTE_NORMAL] {
+            if let Some(values) = waved.attribute(attribute) {
+                mesh.insert_attribute(attribute, values.clone());
+            }
+        }
+    }
+}
+
+/// Builds a flag of two layers facing opposite ways, `THICKNESS` apart, waving at `time`.
+fn flag_mesh(time: f32) -> Mesh {
+    let surface = |u: f32, v: f32| {
+        let x = u * SIZE.x;
+        let phase = x * 2.5 - time * 4.0;
+        Vec3::new(x, (v - 0.5) * SIZE.y, phase.sin() * 0.25 * u)
+    };
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut indices = Vec::new();
+
+    for side in [1.0, -1.0] {
+        let first = positions.len() as u32;
+        for row in 0..=ROWS {
+            for column in 0..=COLUMNS {
+                let (u, v) = (column as f32 / COLUMNS as f32, row as f32 / ROWS as f32);
+                let center = surface(u, v);
+                let du = surface(u + 1e-3, v) - center;
+                let dv = surface(u, v + 1e-3) - center;
+                let normal = du.cross(dv).normalize() * side;
+                positions.push((center + normal * (THICKNESS * 0.5)).to_array());
+                normals.push(normal.to_array());
+            }
+        }
+        for row in 0..ROWS {
+            for column in 0..COLUMNS {
+                let a = first + row * (COLUMNS + 1) + column;
+                let (b, c, d) = (a + 1, a + COLUMNS + 1, a + COLUMNS + 2);
+                if side > 0.0 {
+                    indices.extend([a, b, d, a, d, c]);
+                } else {
+                    indices.extend([a, d, b, a, c, d]);
+                }
+            }
+        }
+    }
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_indices(Indices::U32(indices))
+}
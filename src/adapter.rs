@@ -0,0 +1,76 @@
+//! Falling back to plain materials on unsuitable GPU adapters.
+
+use bevy::{
+    prelude::*,
+    render::{renderer::RenderAdapterInfo, settings::Backends},
+};
+use wgpu_types::DeviceType;
+
+use crate::LineBoilConfig;
+
+/// Matches GPU adapters the boil is disabled on, see
+/// [`LineBoilPlugin::with_adapter_denylist`](crate::LineBoilPlugin::with_adapter_denylist).
+#[derive(Clone, Debug, PartialEq)]
+pub enum LineBoilAdapterFilter {
+    /// Adapters whose name contains this text, ignoring case, e.g. `"llvmpipe"` or
+    /// `"Adreno (TM) 5"`.
+    Name(String),
+    /// Adapters running on any of these backends, e.g. [`Backends::GL`].
+    Backends(Backends),
+    /// Software rasterizers running on the CPU, such as llvmpipe, SwiftShader or WARP.
+    Software,
+}
+
+impl LineBoilAdapterFilter {
+    /// Returns whether the filter matches `adapter`.
+    pub fn matches(&self, adapter: &RenderAdapterInfo) -> bool {
+        match self {
+            Self::Name(name) => adapter
+                .name
+                .to_lowercase()
+                .contains(&name.to_lowercase()),
+            Self::Backends(backends) => backends.contains(Backends::from(adapter.backend)),
+            Self::Software => adapter.device_type == DeviceType::Cpu,
+        }
+    }
+}
+
+/// Inserted at startup when the render adapter matched
+/// [`LineBoilConfig::adapter_denylist`]. While present, no meshes are converted and
+/// [`LineBoil`](crate::LineBoil) entities render with their plain materials.
+///
+/// Remove it to boil anyway; meshes are then converted as usual.
+#[derive(Resource, Clone, Debug)]
+pub struct LineBoilAdapterFallback {
+    /// Name of the adapter the boil was disabled on.
+    pub adapter: String,
+}
+
+/// Checks the render adapter against the denylist, inserting [`LineBoilAdapterFallback`] on
+/// a match. Runs once the renderer is initialized.
+pub(crate) fn check_line_boil_adapter(app: &mut App) {
+    let world = app.world();
+    let (Some(config), Some(adapter)) = (
+        world.get_resource::<LineBoilConfig>(),
+        world.get_resource::<RenderAdapterInfo>(),
+    ) else {
+        return;
+    };
+    let Some(filter) = config
+        .adapter_denylist
+        .iter()
+        .find(|filter| filter.matches(adapter))
+    else {
+        debug!("Line boil enabled on adapter {} ({:?})", adapter.name, adapter.backend);
+        return;
+    };
+
+    warn!(
+        "Line boil disabled on adapter {} ({:?}), matched by {:?}; rendering plain materials",
+        adapter.name, adapter.backend, filter
+    );
+    let fallback = LineBoilAdapterFallback {
+        adapter: adapter.name.clone(),
+    };
+    app.insert_resource(fallback);
+}
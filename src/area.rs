@@ -0,0 +1,123 @@
+//! Per-vertex triangle size used by [`LineBoilSettings::area_response`](crate::LineBoilSettings::area_response).
+
+use bevy::{
+    mesh::{MeshVertexAttribute, PrimitiveTopology, VertexFormat},
+    platform::collections::HashSet,
+    prelude::*,
+};
+
+use crate::{LineBoil, LineBoilApplied};
+
+/// Returns the mesh assets added, loaded or modified since the last read of `mesh_events`.
+pub(crate) fn changed_mesh_assets(
+    mesh_events: &mut MessageReader<AssetEvent<Mesh>>,
+) -> HashSet<AssetId<Mesh>> {
+    mesh_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Added { id }
+            | AssetEvent::Modified { id }
+            | AssetEvent::LoadedWithDependencies { id } => Some(*id),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Vertex attribute holding the local triangle size of each vertex, relative to the mesh average.
+///
+/// Inserted into boiled meshes when
+/// [`LineBoilSettings::area_response`](crate::LineBoilSettings::area_response) is non-zero.
+/// Meshes without it (non-triangle-list topologies, or meshes whose data was unloaded from
+/// the main world) boil with uniform intensity.
+pub const ATTRIBUTE_BOIL_AREA: MeshVertexAttribute =
+    MeshVertexAttribute::new("LineBoil_Area", 988_540_917, VertexFormat::Float32);
+
+/// Shader location of [`ATTRIBUTE_BOIL_AREA`] in `line_boil.wgsl`.
+pub const ATTRIBUTE_BOIL_AREA_LOCATION: u32 = 16;
+
+/// Computes [`ATTRIBUTE_BOIL_AREA`] for boiled meshes whose settings respond to triangle area.
+pub(crate) fn insert_line_boil_area_attribute(
+    mut mesh_events: MessageReader<AssetEvent<Mesh>>,
+    root_query: Query<Ref<LineBoil>>,
+    mesh_query: Query<(Ref<LineBoilApplied>, Ref<Mesh3d>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let changed_meshes = changed_mesh_assets(&mut mesh_events);
+    for (applied, mesh_handle) in mesh_query.iter() {
+        let Ok(line_boil) = root_query.get(applied.root) else {
+            continue;
+        };
+        // Only new meshes, changed mesh assets and changed settings need a look.
+        if !(applied.is_added()
+            || mesh_handle.is_changed()
+            || line_boil.is_changed()
+            || changed_meshes.contains(&mesh_handle.id()))
+        {
+            continue;
+        }
+        if line_boil.settings.area_response == 0.0 {
+            continue;
+        }
+        let Some(mesh) = meshes.get(&mesh_handle.0) else {
+            continue;
+        };
+        // Meshes whose data was unloaded from the main world are skipped.
+        if mesh.try_contains_attribute(ATTRIBUTE_BOIL_AREA).unwrap_or(true) {
+            continue;
+        }
+        let Some(weights) = relative_triangle_sizes(mesh) else {
+            continue;
+        };
+
+        if let Some(mesh) = meshes.get_mut(&mesh_handle.0) {
+            mesh.insert_attribute(ATTRIBUTE_BOIL_AREA, weights);
+        }
+    }
+}
+
+/// Returns, per vertex, the square root of its mean adjacent triangle area divided by the
+/// mesh-wide mean of that value (so 1.0 is an average-sized triangle).
+fn relative_triangle_sizes(mesh: &Mesh) -> Option<Vec<f32>> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    let positions = mesh
+        .try_attribute_option(Mesh::ATTRIBUTE_POSITION)
+        .ok()??
+        .as_float3()?;
+
+    let mut area_sums = vec![0.0; positions.len()];
+    let mut counts = vec![0u32; positions.len()];
+    let indices: Vec<usize> = match mesh.try_indices_option().ok()? {
+        Some(indices) => indices.iter().collect(),
+        None => (0..positions.len()).collect(),
+    };
+
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]];
+        let (Some(pa), Some(pb), Some(pc)) = (positions.get(a), positions.get(b), positions.get(c))
+        else {
+            continue;
+        };
+        let (pa, pb, pc) = (Vec3::from(*pa), Vec3::from(*pb), Vec3::from(*pc));
+        let area = 0.5 * (pb - pa).cross(pc - pa).length();
+
+        for vertex in [a, b, c] {
+            area_sums[vertex] += area;
+            counts[vertex] += 1;
+        }
+    }
+
+    let sizes: Vec<f32> = area_sums
+        .iter()
+        .zip(&counts)
+        .map(|(&sum, &count)| if count == 0 { 0.0 } else { (sum / count as f32).sqrt() })
+        .collect();
+    let used = counts.iter().filter(|&&count| count > 0).count();
+    let mean = sizes.iter().sum::<f32>() / used.max(1) as f32;
+
+    if mean <= f32::EPSILON {
+        return Some(vec![1.0; positions.len()]);
+    }
+    Some(sizes.iter().map(|size| size / mean).collect())
+}
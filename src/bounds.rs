@@ -0,0 +1,58 @@
+//! Conservative bounds of boiled geometry.
+
+use bevy::{camera::primitives::Aabb, prelude::*};
+
+use crate::{DisplacementSpace, LineBoilSettings};
+
+/// Returns a conservative world-space bounding box of `base` (also world-space) with the
+/// boil applied, for culling, picking and sizing gameplay triggers.
+///
+/// The screen-space wobble ([`LineBoilSettings::intensity`], in normalized device
+/// coordinates) has no fixed size in the world, so it is bounded using `world_per_ndc`: the
+/// world-space length of one NDC unit at the farthest distance the mesh is viewed from (see
+/// [`world_per_ndc`]). In [`DisplacementSpace::World`] the intensity is in world units and
+/// `world_per_ndc` is ignored. Both include the peaks of
+/// [`LineBoilSettings::intensity_noise_strength`] and of
+/// [`LineBoilSettings::emissive_boil`] on a fully glowing base. The
+/// [`LineBoilSettings::bias_direction`] lean is exact.
+///
+/// The box is only conservative for `settings` as given. A
+/// [`LineBoilKick`](crate::LineBoilKick) replaces the intensity and
+/// [`LineBoilGlobalScale`](crate::LineBoilGlobalScale) multiplies it, so pass settings with
+/// the kick intensity or a global scale above 1 already applied. Extra scaling by
+/// [`LineBoilSettings::area_response`] on larger-than-average triangles is not included.
+pub fn displaced_aabb(base: &Aabb, settings: &LineBoilSettings, world_per_ndc: f32) -> Aabb {
+    // Noise is in -1..=1 per axis; mirroring may rotate the offset, hence the diagonal.
+    // Emissive boil scales by up to `1 + emissive_boil`, as glow is capped at 1.
+    let peak = settings.intensity.abs()
+        * (1.0 + settings.intensity_noise_strength.abs())
+        * (1.0 + settings.emissive_boil.max(0.0));
+    let wobble = match settings.displacement_space {
+        DisplacementSpace::Screen => peak * std::f32::consts::SQRT_2 * world_per_ndc.abs(),
+        DisplacementSpace::World => peak * 3.0_f32.sqrt(),
+    };
+    let bias = settings.bias_direction.normalize_or_zero() * settings.bias_strength;
+
+    Aabb::from_min_max(
+        Vec3::from(base.min()) + bias - Vec3::splat(wobble),
+        Vec3::from(base.max()) + bias + Vec3::splat(wobble),
+    )
+}
+
+/// Returns the world-space length of one normalized device coordinate unit at `distance`
+/// in front of a camera with `projection`, along its wider screen axis.
+///
+/// Returns `None` for custom projections.
+pub fn world_per_ndc(projection: &Projection, distance: f32) -> Option<f32> {
+    match projection {
+        Projection::Perspective(perspective) => {
+            let half_height = distance * (perspective.fov * 0.5).tan();
+            Some(half_height * perspective.aspect_ratio.max(1.0))
+        }
+        Projection::Orthographic(orthographic) => {
+            let size = orthographic.area.size();
+            Some(size.x.max(size.y) * 0.5)
+        }
+        Projection::Custom(_) => None,
+    }
+}
@@ -0,0 +1,217 @@
+//! The master time the boil animates on.
+
+use bevy::prelude::*;
+
+use crate::LineBoil;
+
+/// Master boil time, uploaded to every line boil material each frame.
+///
+/// Advances with [`Time`] by default. Timeline and cutscene tooling can read it, or set it
+/// to sync the boil to an external clock or scrub it; a value set with
+/// [`set_elapsed`](Self::set_elapsed) is uploaded unchanged on the next upload and
+/// advances normally from there.
+///
+/// For networked games, switch to [`LineBoilTimeMode::Authoritative`] and feed the server's
+/// boil time with [`set_authoritative`](Self::set_authoritative) whenever it arrives, so every
+/// client shows the same wobble.
+///
+/// The clock can be [paused](Self::set_paused), freezing every boil on its current held
+/// frame, and [scaled](Self::set_time_scale) independently of [`Time`], e.g. to ramp the
+/// boil for a cutscene.
+#[derive(Resource, Clone, Debug)]
+pub struct LineBoilClock {
+    elapsed: f32,
+    set_externally: bool,
+    mode: LineBoilTimeMode,
+    authoritative: Option<f32>,
+    paused: bool,
+    time_scale: f32,
+}
+
+impl Default for LineBoilClock {
+    fn default() -> Self {
+        Self {
+            elapsed: 0.0,
+            set_externally: false,
+            mode: LineBoilTimeMode::default(),
+            authoritative: None,
+            paused: false,
+            time_scale: 1.0,
+        }
+    }
+}
+
+/// How [`LineBoilClock`] advances.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LineBoilTimeMode {
+    /// Accumulates local frame time.
+    #[default]
+    Accumulate,
+    /// Follows the time given to [`LineBoilClock::set_authoritative`], extrapolated with
+    /// local frame time between updates.
+    ///
+    /// Corrections are eased in over roughly `smoothing` seconds so the boil doesn't jump
+    /// when a late or early update arrives; 0 snaps to every update.
+    Authoritative {
+        /// Time constant in seconds over which the clock converges on the authoritative time.
+        smoothing: f32,
+    },
+}
+
+impl LineBoilClock {
+    /// Returns the boil time in seconds.
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Sets the boil time in seconds.
+    pub fn set_elapsed(&mut self, elapsed: f32) {
+        self.elapsed = elapsed;
+        self.set_externally = true;
+    }
+
+    /// Resets the boil time to zero.
+    pub fn reset(&mut self) {
+        self.set_elapsed(0.0);
+    }
+
+    /// Returns the index of the held frame shown at `frame_rate`
+    /// (see [`LineBoilSettings::frame_rate`](crate::LineBoilSettings::frame_rate)).
+    ///
+    /// The index counts up without wrapping, while the noise time wraps every 4096 held
+    /// frames: without [`seed_drift`](crate::LineBoilSettings::seed_drift), frames 4096
+    /// apart show the same drawing.
+    pub fn held_frame(&self, frame_rate: f32) -> u32 {
+        (self.elapsed * frame_rate).floor() as u32
+    }
+
+    /// Sets the boil time to the middle of held frame `frame` at `frame_rate`.
+    ///
+    /// Stepping through consecutive frames shows each drawing of the boil exactly once, e.g.
+    /// for capturing an image sequence. Like [`set_elapsed`](Self::set_elapsed), the clock
+    /// resumes from there unless it is set again every frame.
+    pub fn set_held_frame(&mut self, frame: u32, frame_rate: f32) {
+        self.set_elapsed((frame as f32 + 0.5) / frame_rate);
+    }
+
+    /// Returns whether the clock is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Pauses or resumes the clock.
+    ///
+    /// While paused the boil time stops advancing, so every boil holds its current frame,
+    /// including roots with a [`time_scale_override`](LineBoil::time_scale_override). The
+    /// time can still be set, e.g. to step through frames while paused.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Returns the rate at which the clock advances relative to [`Time`].
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Sets the rate at which the clock advances relative to [`Time`], on top of any
+    /// relative speed of virtual time. Defaults to 1.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = time_scale;
+    }
+
+    /// Returns how the clock advances.
+    pub fn mode(&self) -> LineBoilTimeMode {
+        self.mode
+    }
+
+    /// Sets how the clock advances.
+    pub fn set_mode(&mut self, mode: LineBoilTimeMode) {
+        self.mode = mode;
+        if mode == LineBoilTimeMode::Accumulate {
+            self.authoritative = None;
+        }
+    }
+
+    /// Feeds the authoritative boil time in seconds, e.g. as replicated from the server.
+    ///
+    /// Ignored unless the clock is in [`LineBoilTimeMode::Authoritative`]. The first value is
+    /// adopted as-is; later values are smoothed towards.
+    pub fn set_authoritative(&mut self, elapsed: f32) {
+        if self.mode == LineBoilTimeMode::Accumulate {
+            return;
+        }
+        if self.authoritative.is_none() {
+            self.set_elapsed(elapsed);
+        }
+        self.authoritative = Some(elapsed);
+    }
+}
+
+/// Boil time of a [`LineBoil`] root with a
+/// [`time_scale_override`](LineBoil::time_scale_override), used instead of [`LineBoilClock`].
+#[derive(Component)]
+pub(crate) struct LineBoilOwnTime(pub f32);
+
+impl LineBoilOwnTime {
+    /// Returns the boil time of a root, given its own time if it has one.
+    pub(crate) fn or_clock(own_time: Option<&Self>, clock: &LineBoilClock) -> f32 {
+        own_time.map_or(clock.elapsed(), |own_time| own_time.0)
+    }
+}
+
+/// Advances the own time of roots overriding the time scale, starting from the master time
+/// so switching the override on doesn't jump, and moving with it whenever it is set.
+pub(crate) fn tick_line_boil_own_time(
+    mut commands: Commands,
+    real_time: Res<Time<Real>>,
+    clock: Res<LineBoilClock>,
+    mut root_query: Query<(Entity, &LineBoil, Option<&mut LineBoilOwnTime>)>,
+) {
+    for (entity, line_boil, own_time) in root_query.iter_mut() {
+        match (line_boil.time_scale_override, own_time) {
+            (Some(time_scale), Some(mut own_time)) => {
+                if clock.set_externally {
+                    own_time.0 = clock.elapsed();
+                } else if !clock.paused {
+                    own_time.0 += real_time.delta_secs() * time_scale;
+                }
+            }
+            (Some(_), None) => {
+                commands.entity(entity).insert(LineBoilOwnTime(clock.elapsed()));
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<LineBoilOwnTime>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Advances [`LineBoilClock`], unless it is paused or was set since the last upload.
+pub(crate) fn tick_line_boil_clock(time: Res<Time>, mut clock: ResMut<LineBoilClock>) {
+    if clock.set_externally {
+        clock.set_externally = false;
+        return;
+    }
+    if clock.paused {
+        return;
+    }
+    let delta = time.delta_secs();
+    clock.elapsed += delta * clock.time_scale;
+
+    let LineBoilTimeMode::Authoritative { smoothing } = clock.mode else {
+        return;
+    };
+    // The server's time is unaffected by the local time scale.
+    let Some(target) = clock.authoritative.as_mut() else {
+        return;
+    };
+    *target += delta;
+    let target = *target;
+    let blend = if smoothing > 0.0 {
+        1.0 - (-delta / smoothing).exp()
+    } else {
+        1.0
+    };
+    clock.elapsed += (target - clock.elapsed) * blend;
+}
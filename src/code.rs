@@ -0,0 +1,397 @@
+//! Short, pasteable codes for sharing [`LineBoilSettings`].
+//!
+//! A code is `lb1-` followed by unpadded URL-safe base64 of a list of fields. Each field is
+//! stored as its id, the payload length and the payload, and only fields that differ from
+//! zero (or `false` / `None`) are written, so codes stay short. Readers skip ids they don't
+//! know, and fields missing from a code decode as zero, which disables them. New settings
+//! therefore only need a fresh id: old codes keep working in newer versions, and new codes
+//! still load (without the new fields) in older ones. Ids must never be reused.
+
+use std::fmt;
+
+use bevy::math::Vec3;
+
+use crate::{DisplacementSpace, LineBoilSettings, MaskAttribute, MirrorAxis, NoiseKind};
+
+/// Prefix of codes in the current format.
+const CODE_PREFIX: &str = "lb1-";
+
+const INTENSITY: u8 = 0;
+const FRAME_RATE: u8 = 1;
+const NOISE_FREQUENCY: u8 = 2;
+const NOISE_FREQUENCY_AXES: u8 = 3;
+const SEED: u8 = 4;
+const SEED_DRIFT: u8 = 5;
+const AREA_RESPONSE: u8 = 6;
+const CREASE_PRESERVATION: u8 = 7;
+const SHEET_THICKNESS: u8 = 8;
+const PER_INSTANCE_SEED: u8 = 9;
+const MIRROR_AXIS: u8 = 10;
+const CLIP_SPACE_SHAKE: u8 = 11;
+const BIAS_DIRECTION: u8 = 12;
+const BIAS_STRENGTH: u8 = 13;
+const DETAIL_NORMAL_STRENGTH: u8 = 14;
+const EMISSIVE_BOIL: u8 = 15;
+const EMISSIVE_PULSE: u8 = 16;
+const BOIL_SHADOWS: u8 = 17;
+const INTENSITY_NOISE_STRENGTH: u8 = 18;
+const INTENSITY_NOISE_FREQUENCY: u8 = 19;
+const RECOMPUTE_NORMALS: u8 = 20;
+const DISPLACEMENT_SPACE: u8 = 21;
+const MASK_ATTRIBUTE: u8 = 22;
+const FALLOFF_START: u8 = 23;
+const FALLOFF_END: u8 = 24;
+const NOISE_KIND: u8 = 25;
+const FRAME_BLEND: u8 = 26;
+const REGION_MIN: u8 = 27;
+const REGION_MAX: u8 = 28;
+const REGION_MARGIN: u8 = 29;
+const TIME_OFFSET: u8 = 30;
+
+/// Settings a code is decoded on top of: every field disabled.
+const CODE_BASE: LineBoilSettings = LineBoilSettings {
+    intensity: 0.0,
+    frame_rate: 0.0,
+    frame_blend: 0.0,
+    time_offset: 0.0,
+    noise_frequency: 0.0,
+    noise_kind: NoiseKind::Value,
+    noise_frequency_axes: Vec3::ZERO,
+    intensity_noise_strength: 0.0,
+    intensity_noise_frequency: 0.0,
+    falloff_start: 0.0,
+    falloff_end: 0.0,
+    displacement_space: DisplacementSpace::Screen,
+    seed: 0.0,
+    seed_drift: 0.0,
+    area_response: 0.0,
+    crease_preservation: 0.0,
+    mask_attribute: None,
+    region_min: Vec3::ZERO,
+    region_max: Vec3::ZERO,
+    region_margin: 0.0,
+    sheet_thickness: 0.0,
+    per_instance_seed: false,
+    mirror_axis: None,
+    clip_space_shake: false,
+    bias_direction: Vec3::ZERO,
+    bias_strength: 0.0,
+    detail_normal_strength: 0.0,
+    emissive_boil: 0.0,
+    emissive_pulse: 0.0,
+    boil_shadows: false,
+    recompute_normals: false,
+};
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Error returned by [`LineBoilSettings::from_code`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineBoilCodeError {
+    /// The code doesn't start with a known `lb<version>-` prefix, e.g. it was made by a newer,
+    /// incompatible version of the format.
+    UnsupportedVersion,
+    /// The code is truncated or contains invalid characters or values.
+    Malformed,
+}
+
+impl fmt::Display for LineBoilCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion => write!(f, "unsupported line boil code version"),
+            Self::Malformed => write!(f, "malformed line boil code"),
+        }
+    }
+}
+
+impl std::error::Error for LineBoilCodeError {}
+
+impl LineBoilSettings {
+    /// Encodes the settings as a short code such as `lb1-AARvEgM8...`, for sharing a look by
+    /// pasting it in chat. Decode it with [`from_code`](Self::from_code).
+    pub fn to_code(&self) -> String {
+        let mut bytes = Vec::new();
+        let mut write = |id: u8, payload: &[u8]| {
+            bytes.push(id);
+            bytes.push(payload.len() as u8);
+            bytes.extend_from_slice(payload);
+        };
+
+        for (id, value) in [
+            (INTENSITY, self.intensity),
+            (FRAME_RATE, self.frame_rate),
+            (FRAME_BLEND, self.frame_blend),
+            (TIME_OFFSET, self.time_offset),
+            (NOISE_FREQUENCY, self.noise_frequency),
+            (INTENSITY_NOISE_STRENGTH, self.intensity_noise_strength),
+            (INTENSITY_NOISE_FREQUENCY, self.intensity_noise_frequency),
+            (FALLOFF_START, self.falloff_start),
+            (FALLOFF_END, self.falloff_end),
+            (SEED, self.seed),
+            (SEED_DRIFT, self.seed_drift),
+            (AREA_RESPONSE, self.area_response),
+            (CREASE_PRESERVATION, self.crease_preservation),
+            (REGION_MARGIN, self.region_margin),
+            (SHEET_THICKNESS, self.sheet_thickness),
+            (BIAS_STRENGTH, self.bias_strength),
+            (DETAIL_NORMAL_STRENGTH, self.detail_normal_strength),
+            (EMISSIVE_BOIL, self.emissive_boil),
+            (EMISSIVE_PULSE, self.emissive_pulse),
+        ] {
+            if value != 0.0 {
+                write(id, &value.to_le_bytes());
+            }
+        }
+        for (id, value) in [
+            (NOISE_FREQUENCY_AXES, self.noise_frequency_axes),
+            (BIAS_DIRECTION, self.bias_direction),
+            (REGION_MIN, self.region_min),
+            (REGION_MAX, self.region_max),
+        ] {
+            if value != Vec3::ZERO {
+                let payload: Vec<u8> =
+                    value.to_array().iter().flat_map(|v| v.to_le_bytes()).collect();
+                write(id, &payload);
+            }
+        }
+        for (id, value) in [
+            (PER_INSTANCE_SEED, self.per_instance_seed),
+            (CLIP_SPACE_SHAKE, self.clip_space_shake),
+            (BOIL_SHADOWS, self.boil_shadows),
+            (RECOMPUTE_NORMALS, self.recompute_normals),
+        ] {
+            if value {
+                write(id, &[]);
+            }
+        }
+        if let Some(axis) = self.mirror_axis {
+            let axis = match axis {
+                MirrorAxis::X => 0,
+                MirrorAxis::Y => 1,
+                MirrorAxis::Z => 2,
+            };
+            write(MIRROR_AXIS, &[axis]);
+        }
+        if self.noise_kind != NoiseKind::Value {
+            let kind = match self.noise_kind {
+                NoiseKind::Value => 0,
+                NoiseKind::Simplex => 1,
+                NoiseKind::Curl => 2,
+            };
+            write(NOISE_KIND, &[kind]);
+        }
+        if let Some(mask) = self.mask_attribute {
+            let mask = match mask {
+                MaskAttribute::ColorRed => 0,
+                MaskAttribute::ColorGreen => 1,
+                MaskAttribute::ColorBlue => 2,
+                MaskAttribute::ColorAlpha => 3,
+                MaskAttribute::BoilMask => 4,
+            };
+            write(MASK_ATTRIBUTE, &[mask]);
+        }
+        if self.displacement_space == DisplacementSpace::World {
+            write(DISPLACEMENT_SPACE, &[1]);
+        }
+
+        format!("{CODE_PREFIX}{}", encode_base64(&bytes))
+    }
+
+    /// Decodes settings from a code made by [`to_code`](Self::to_code). Surrounding
+    /// whitespace is ignored.
+    ///
+    /// Codes from older versions of this crate decode with the settings they didn't know
+    /// about disabled; settings this version doesn't know about are skipped.
+    pub fn from_code(code: &str) -> Result<Self, LineBoilCodeError> {
+        let code = code.trim();
+        let Some(data) = code.strip_prefix(CODE_PREFIX) else {
+            return Err(if code.starts_with("lb") {
+                LineBoilCodeError::UnsupportedVersion
+            } else {
+                LineBoilCodeError::Malformed
+            });
+        };
+        let bytes = decode_base64(data).ok_or(LineBoilCodeError::Malformed)?;
+
+        let mut settings = CODE_BASE;
+        let mut rest = bytes.as_slice();
+        while let [id, len, tail @ ..] = rest {
+            let len = *len as usize;
+            if tail.len() < len {
+                return Err(LineBoilCodeError::Malformed);
+            }
+            let (payload, tail) = tail.split_at(len);
+            rest = tail;
+
+            match *id {
+                INTENSITY => settings.intensity = read_f32(payload)?,
+                FRAME_RATE => settings.frame_rate = read_f32(payload)?,
+                NOISE_FREQUENCY => settings.noise_frequency = read_f32(payload)?,
+                NOISE_FREQUENCY_AXES => settings.noise_frequency_axes = read_vec3(payload)?,
+                SEED => settings.seed = read_f32(payload)?,
+                SEED_DRIFT => settings.seed_drift = read_f32(payload)?,
+                AREA_RESPONSE => settings.area_response = read_f32(payload)?,
+                CREASE_PRESERVATION => settings.crease_preservation = read_f32(payload)?,
+                SHEET_THICKNESS => settings.sheet_thickness = read_f32(payload)?,
+                PER_INSTANCE_SEED => settings.per_instance_seed = true,
+                MIRROR_AXIS => {
+                    settings.mirror_axis = Some(match payload {
+                        [0] => MirrorAxis::X,
+                        [1] => MirrorAxis::Y,
+                        [2] => MirrorAxis::Z,
+                        _ => return Err(LineBoilCodeError::Malformed),
+                    });
+                }
+                CLIP_SPACE_SHAKE => settings.clip_space_shake = true,
+                BIAS_DIRECTION => settings.bias_direction = read_vec3(payload)?,
+                REGION_MIN => settings.region_min = read_vec3(payload)?,
+                REGION_MAX => settings.region_max = read_vec3(payload)?,
+                REGION_MARGIN => settings.region_margin = read_f32(payload)?,
+                BIAS_STRENGTH => settings.bias_strength = read_f32(payload)?,
+                DETAIL_NORMAL_STRENGTH => settings.detail_normal_strength = read_f32(payload)?,
+                EMISSIVE_BOIL => settings.emissive_boil = read_f32(payload)?,
+                EMISSIVE_PULSE => settings.emissive_pulse = read_f32(payload)?,
+                BOIL_SHADOWS => settings.boil_shadows = true,
+                RECOMPUTE_NORMALS => settings.recompute_normals = true,
+                NOISE_KIND => {
+                    settings.noise_kind = match payload {
+                        [0] => NoiseKind::Value,
+                        [1] => NoiseKind::Simplex,
+                        [2] => NoiseKind::Curl,
+                        _ => return Err(LineBoilCodeError::Malformed),
+                    };
+                }
+                FRAME_BLEND => settings.frame_blend = read_f32(payload)?,
+                TIME_OFFSET => settings.time_offset = read_f32(payload)?,
+                FALLOFF_START => settings.falloff_start = read_f32(payload)?,
+                FALLOFF_END => settings.falloff_end = read_f32(payload)?,
+                MASK_ATTRIBUTE => {
+                    settings.mask_attribute = Some(match payload {
+                        [0] => MaskAttribute::ColorRed,
+                        [1] => MaskAttribute::ColorGreen,
+                        [2] => MaskAttribute::ColorBlue,
+                        [3] => MaskAttribute::ColorAlpha,
+                        [4] => MaskAttribute::BoilMask,
+                        _ => return Err(LineBoilCodeError::Malformed),
+                    });
+                }
+                DISPLACEMENT_SPACE => {
+                    settings.displacement_space = match payload {
+                        [0] => DisplacementSpace::Screen,
+                        [1] => DisplacementSpace::World,
+                        _ => return Err(LineBoilCodeError::Malformed),
+                    };
+                }
+                INTENSITY_NOISE_STRENGTH => {
+                    settings.intensity_noise_strength = read_f32(payload)?;
+                }
+                INTENSITY_NOISE_FREQUENCY => {
+                    settings.intensity_noise_frequency = read_f32(payload)?;
+                }
+                // Written by a newer version of this crate.
+                _ => {}
+            }
+        }
+        if !rest.is_empty() {
+            return Err(LineBoilCodeError::Malformed);
+        }
+
+        Ok(settings)
+    }
+}
+
+fn read_f32(payload: &[u8]) -> Result<f32, LineBoilCodeError> {
+    let bytes = payload.try_into().map_err(|_| LineBoilCodeError::Malformed)?;
+    let value = f32::from_le_bytes(bytes);
+    if !value.is_finite() {
+        return Err(LineBoilCodeError::Malformed);
+    }
+    Ok(value)
+}
+
+fn read_vec3(payload: &[u8]) -> Result<Vec3, LineBoilCodeError> {
+    if payload.len() != 12 {
+        return Err(LineBoilCodeError::Malformed);
+    }
+    Ok(Vec3::new(
+        read_f32(&payload[0..4])?,
+        read_f32(&payload[4..8])?,
+        read_f32(&payload[8..12])?,
+    ))
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &byte)| n | (byte as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(BASE64_ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+        }
+    }
+    out
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.as_bytes().chunks(4) {
+        // A lone trailing character can't hold a whole byte.
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let value = BASE64_ALPHABET.iter().position(|&a| a == c)? as u32;
+            n |= value << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_round_trip() {
+        for settings in [LineBoilSettings::SUBTLE, LineBoilSettings::AGGRESSIVE] {
+            assert_eq!(LineBoilSettings::from_code(&settings.to_code()), Ok(settings));
+        }
+    }
+
+    #[test]
+    fn base64_round_trips_every_tail_length() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for len in 0..8 {
+            assert_eq!(decode_base64(&encode_base64(&bytes[..len])), Some(bytes[..len].to_vec()));
+        }
+    }
+
+    #[test]
+    fn unknown_fields_are_skipped() {
+        let mut bytes = vec![200, 3, 1, 2, 3, INTENSITY, 4];
+        bytes.extend_from_slice(&0.25f32.to_le_bytes());
+        let code = format!("{CODE_PREFIX}{}", encode_base64(&bytes));
+
+        let settings = LineBoilSettings::from_code(&code).unwrap();
+        assert_eq!(settings, LineBoilSettings { intensity: 0.25, ..CODE_BASE });
+    }
+
+    #[test]
+    fn rejects_bad_codes() {
+        assert_eq!(
+            LineBoilSettings::from_code("lb2-AAAA"),
+            Err(LineBoilCodeError::UnsupportedVersion)
+        );
+        assert_eq!(LineBoilSettings::from_code("hello"), Err(LineBoilCodeError::Malformed));
+        // Intensity claiming a four byte payload with only two present.
+        let code = format!("{CODE_PREFIX}{}", encode_base64(&[INTENSITY, 4, 0, 0]));
+        assert_eq!(LineBoilSettings::from_code(&code), Err(LineBoilCodeError::Malformed));
+    }
+}
@@ -0,0 +1,76 @@
+//! Command shortcuts for tweaking a live [`LineBoil`].
+
+use bevy::prelude::*;
+
+use crate::{LineBoil, LineBoilConfig, LineBoilKick, LineBoilSettings};
+
+/// Extension methods on [`EntityCommands`] changing one setting of an entity's [`LineBoil`].
+///
+/// Changes are copied into the entity's converted materials, so they show up on the next
+/// frame. Entities without a [`LineBoil`] are left untouched.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// commands.entity(enemy).set_boil_intensity(0.03);
+/// ```
+pub trait LineBoilCommandsExt {
+    /// Sets [`LineBoilSettings::intensity`].
+    fn set_boil_intensity(&mut self, intensity: f32) -> &mut Self;
+
+    /// Sets [`LineBoilSettings::frame_rate`].
+    fn set_boil_frame_rate(&mut self, frame_rate: f32) -> &mut Self;
+
+    /// Sets [`LineBoilSettings::noise_frequency`].
+    fn set_boil_noise_frequency(&mut self, noise_frequency: f32) -> &mut Self;
+
+    /// Applies `modify` to the settings.
+    fn modify_boil_settings(
+        &mut self,
+        modify: impl FnOnce(&mut LineBoilSettings) + Send + 'static,
+    ) -> &mut Self;
+
+    /// Spikes the intensity to `intensity` for one held frame, see [`LineBoilKick`].
+    fn kick_boil(&mut self, intensity: f32) -> &mut Self;
+}
+
+impl LineBoilCommandsExt for EntityCommands<'_> {
+    fn set_boil_intensity(&mut self, intensity: f32) -> &mut Self {
+        self.modify_boil_settings(move |settings| settings.intensity = intensity)
+    }
+
+    fn set_boil_frame_rate(&mut self, frame_rate: f32) -> &mut Self {
+        self.modify_boil_settings(move |settings| settings.frame_rate = frame_rate)
+    }
+
+    fn set_boil_noise_frequency(&mut self, noise_frequency: f32) -> &mut Self {
+        self.modify_boil_settings(move |settings| settings.noise_frequency = noise_frequency)
+    }
+
+    fn modify_boil_settings(
+        &mut self,
+        modify: impl FnOnce(&mut LineBoilSettings) + Send + 'static,
+    ) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            let default_settings = entity
+                .world()
+                .get_resource::<LineBoilConfig>()
+                .map(|config| config.default_settings);
+            let Some(mut line_boil) = entity.get_mut::<LineBoil>() else {
+                return;
+            };
+            // Resolve pending defaults first so they don't overwrite this change.
+            if line_boil.use_default_settings {
+                if let Some(default_settings) = default_settings {
+                    line_boil.settings = default_settings;
+                }
+                line_boil.use_default_settings = false;
+            }
+            modify(&mut line_boil.settings);
+        })
+    }
+
+    fn kick_boil(&mut self, intensity: f32) -> &mut Self {
+        self.insert(LineBoilKick::new(intensity))
+    }
+}
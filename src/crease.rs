@@ -0,0 +1,101 @@
+//! Per-vertex hard edge weight used by [`LineBoilSettings::crease_preservation`](crate::LineBoilSettings::crease_preservation).
+
+use bevy::{
+    mesh::{MeshVertexAttribute, VertexFormat},
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use crate::{LineBoil, LineBoilApplied, area::changed_mesh_assets};
+
+/// Vertex attribute holding how sharp a crease each vertex sits on, from 0 (smooth) to 1
+/// (a hard edge of 90° or more).
+///
+/// Inserted into boiled meshes when
+/// [`LineBoilSettings::crease_preservation`](crate::LineBoilSettings::crease_preservation)
+/// is non-zero, unless the mesh already provides it. Hard edges are detected where vertices
+/// share a position but not a normal, which is how glTF exporters split them; meshes can
+/// instead supply their own weights under this attribute (e.g. painted in a DCC tool).
+pub const ATTRIBUTE_BOIL_CREASE: MeshVertexAttribute =
+    MeshVertexAttribute::new("LineBoil_Crease", 988_540_918, VertexFormat::Float32);
+
+/// Shader location of [`ATTRIBUTE_BOIL_CREASE`] in the line boil vertex shaders.
+pub const ATTRIBUTE_BOIL_CREASE_LOCATION: u32 = 17;
+
+/// Positions closer than this are treated as the same split vertex.
+const WELD_DISTANCE: f32 = 1e-4;
+
+/// Computes [`ATTRIBUTE_BOIL_CREASE`] for boiled meshes whose settings preserve creases.
+pub(crate) fn insert_line_boil_crease_attribute(
+    mut mesh_events: MessageReader<AssetEvent<Mesh>>,
+    root_query: Query<Ref<LineBoil>>,
+    mesh_query: Query<(Ref<LineBoilApplied>, Ref<Mesh3d>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let changed_meshes = changed_mesh_assets(&mut mesh_events);
+    for (applied, mesh_handle) in mesh_query.iter() {
+        let Ok(line_boil) = root_query.get(applied.root) else {
+            continue;
+        };
+        // Only new meshes, changed mesh assets and changed settings need a look.
+        if !(applied.is_added()
+            || mesh_handle.is_changed()
+            || line_boil.is_changed()
+            || changed_meshes.contains(&mesh_handle.id()))
+        {
+            continue;
+        }
+        if line_boil.settings.crease_preservation == 0.0 {
+            continue;
+        }
+        let Some(mesh) = meshes.get(&mesh_handle.0) else {
+            continue;
+        };
+        // Meshes whose data was unloaded from the main world are skipped.
+        if mesh.try_contains_attribute(ATTRIBUTE_BOIL_CREASE).unwrap_or(true) {
+            continue;
+        }
+        let Some(weights) = crease_weights(mesh) else {
+            continue;
+        };
+
+        if let Some(mesh) = meshes.get_mut(&mesh_handle.0) {
+            mesh.insert_attribute(ATTRIBUTE_BOIL_CREASE, weights);
+        }
+    }
+}
+
+/// Returns, per vertex, `1 - cos` of the widest angle between its normal and the normals of
+/// other vertices at the same position, clamped to `0..=1`.
+fn crease_weights(mesh: &Mesh) -> Option<Vec<f32>> {
+    let positions = mesh
+        .try_attribute_option(Mesh::ATTRIBUTE_POSITION)
+        .ok()??
+        .as_float3()?;
+    let normals = mesh
+        .try_attribute_option(Mesh::ATTRIBUTE_NORMAL)
+        .ok()??
+        .as_float3()?;
+    if normals.len() != positions.len() {
+        return None;
+    }
+
+    let mut welded: HashMap<[i32; 3], Vec<usize>> = HashMap::default();
+    for (index, position) in positions.iter().enumerate() {
+        let key = position.map(|component| (component / WELD_DISTANCE).round() as i32);
+        welded.entry(key).or_default().push(index);
+    }
+
+    let mut weights = vec![0.0; positions.len()];
+    for group in welded.values().filter(|group| group.len() > 1) {
+        for &vertex in group {
+            let normal = Vec3::from(normals[vertex]).normalize_or_zero();
+            let min_dot = group
+                .iter()
+                .map(|&other| normal.dot(Vec3::from(normals[other]).normalize_or_zero()))
+                .fold(1.0, f32::min);
+            weights[vertex] = (1.0 - min_dot).clamp(0.0, 1.0);
+        }
+    }
+    Some(weights)
+}
@@ -0,0 +1,49 @@
+//! Gameplay-driven boil strength.
+
+use bevy::prelude::*;
+
+use crate::LineBoilSettings;
+
+/// Normalized `0..=1` gameplay value scaling the boil of a [`LineBoil`](crate::LineBoil) root.
+///
+/// The effective intensity is the root's intensity multiplied by the drive, so writing e.g.
+/// `1.0 - health_fraction` every frame makes an enemy boil more violently as it weakens.
+/// Add a [`LineBoilDriveMapping`] to also map the drive onto frame rate and noise frequency.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct LineBoilDrive(pub f32);
+
+impl Default for LineBoilDrive {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Optional mapping of a [`LineBoilDrive`] onto other parameters.
+///
+/// Each range is `(value at drive 0, value at drive 1)`, linearly interpolated. `None`
+/// leaves the root's own value untouched.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct LineBoilDriveMapping {
+    /// Frame rate range, e.g. `(4.0, 12.0)` to redraw faster as the drive rises.
+    pub frame_rate: Option<(f32, f32)>,
+    /// Noise frequency range, e.g. `(6.0, 14.0)` for tighter turbulence as the drive rises.
+    pub noise_frequency: Option<(f32, f32)>,
+}
+
+impl LineBoilDrive {
+    /// Applies the drive (and its optional mapping) to `settings`.
+    pub fn apply(&self, mapping: Option<&LineBoilDriveMapping>, settings: &mut LineBoilSettings) {
+        let drive = self.0.clamp(0.0, 1.0);
+        settings.intensity *= drive;
+
+        let Some(mapping) = mapping else {
+            return;
+        };
+        if let Some((low, high)) = mapping.frame_rate {
+            settings.frame_rate = low.lerp(high, drive);
+        }
+        if let Some((low, high)) = mapping.noise_frequency {
+            settings.noise_frequency = low.lerp(high, drive);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Global boil strength driven from a schedule such as time of day.
+
+use std::sync::Arc;
+
+use bevy::{math::curve::Curve, prelude::*};
+
+/// Global scalar multiplying the intensity of every line boil.
+///
+/// Write [`input`](Self::input) from a day/night cycle or similar schedule. With a
+/// [response curve](Self::with_response) the input is mapped through it (sampled clamped to
+/// the curve's domain), otherwise it is used as the multiplier directly.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // 0 at noon, 1 at midnight: calm by day, shaky at night.
+/// app.insert_resource(LineBoilGlobalScale::default().with_response(
+///     EasingCurve::new(1.0, 3.0, EaseFunction::QuadraticIn),
+/// ));
+///
+/// fn follow_night(clock: Res<DayNight>, mut scale: ResMut<LineBoilGlobalScale>) {
+///     scale.input = clock.darkness();
+/// }
+/// ```
+#[derive(Resource, Clone)]
+pub struct LineBoilGlobalScale {
+    /// Schedule value, e.g. how far into the night it is.
+    pub input: f32,
+    response: Option<Arc<dyn Curve<f32> + Send + Sync>>,
+}
+
+impl Default for LineBoilGlobalScale {
+    fn default() -> Self {
+        Self {
+            input: 1.0,
+            response: None,
+        }
+    }
+}
+
+impl LineBoilGlobalScale {
+    /// Maps [`input`](Self::input) through `response` to get the intensity multiplier.
+    pub fn with_response(mut self, response: impl Curve<f32> + Send + Sync + 'static) -> Self {
+        self.response = Some(Arc::new(response));
+        self
+    }
+
+    /// Removes the response curve, using [`input`](Self::input) as the multiplier directly.
+    pub fn without_response(mut self) -> Self {
+        self.response = None;
+        self
+    }
+
+    /// Returns the current intensity multiplier.
+    pub fn scale(&self) -> f32 {
+        match &self.response {
+            Some(response) => response.sample_clamped(self.input),
+            None => self.input,
+        }
+    }
+}
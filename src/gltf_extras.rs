@@ -0,0 +1,41 @@
+//! Line boil authored in glTF node extras.
+
+use bevy::{gltf::GltfExtras, prelude::*};
+
+use crate::LineBoil;
+
+/// Key in a node's extras naming the [`LineBoil`] preset for its subtree.
+pub const GLTF_EXTRAS_KEY: &str = "line_boil";
+
+/// Marks a [`LineBoil`] inserted from glTF extras, whose nesting is intentional.
+#[derive(Component)]
+pub(crate) struct LineBoilFromExtras;
+
+/// Inserts the [`LineBoil`] preset named by e.g. `{"line_boil": "aggressive"}` on newly
+/// spawned glTF nodes, so the tagged subtree boils with no gameplay code.
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_line_boil_gltf_extras(
+    mut commands: Commands,
+    extras_query: Query<(Entity, &GltfExtras), (Added<GltfExtras>, Without<LineBoil>)>,
+) {
+    for (entity, extras) in extras_query.iter() {
+        let Ok(serde_json::Value::Object(extras)) = serde_json::from_str(&extras.value) else {
+            continue;
+        };
+        let Some(value) = extras.get(GLTF_EXTRAS_KEY) else {
+            continue;
+        };
+
+        match value.as_str().and_then(LineBoil::preset) {
+            Some(line_boil) => {
+                commands
+                    .entity(entity)
+                    .insert((line_boil, LineBoilFromExtras));
+            }
+            None => warn!(
+                "Unknown line boil preset {value} in glTF extras of {entity}; \
+                 expected \"subtle\" or \"aggressive\""
+            ),
+        }
+    }
+}
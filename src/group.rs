@@ -0,0 +1,62 @@
+//! Boil groups redrawing on a shared beat.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+/// Puts a [`LineBoil`](crate::LineBoil) root into a boil group, so the whole group redraws
+/// on the same beat while each member keeps its own wobble.
+///
+/// All roots with the same group id boil on one time and frame rate, taken from the
+/// group's leader (the member spawned first, by entity order), so their held frames change
+/// at exactly the same moment. Each member's seed is offset by a value derived from its
+/// entity, so even identical models in a group draw different patterns. Everything else
+/// (intensity, drives, active windows) stays per member.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// for prop in props {
+///     commands.spawn((SceneRoot(prop), LineBoil::subtle(), LineBoilGroup(0)));
+/// }
+/// ```
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LineBoilGroup(pub u32);
+
+/// Seed offsets of roots wrap after this many entity indices, keeping noise inputs small.
+const GROUP_SEED_WRAP: u32 = 4096;
+
+/// Time and frame rate a group boils on, from its leader.
+#[derive(Clone, Copy)]
+pub(crate) struct LineBoilBeat {
+    leader: Entity,
+    pub time: f32,
+    pub frame_rate: f32,
+}
+
+/// Collects the beat of every group from `(root, group, time, frame_rate)` of its members.
+pub(crate) fn group_beats(
+    members: impl IntoIterator<Item = (Entity, LineBoilGroup, f32, f32)>,
+) -> HashMap<LineBoilGroup, LineBoilBeat> {
+    let mut beats = HashMap::<LineBoilGroup, LineBoilBeat>::default();
+    for (root, group, time, frame_rate) in members {
+        let beat = LineBoilBeat {
+            leader: root,
+            time,
+            frame_rate,
+        };
+        beats
+            .entry(group)
+            .and_modify(|current| {
+                if root < current.leader {
+                    *current = beat;
+                }
+            })
+            .or_insert(beat);
+    }
+    beats
+}
+
+/// Seed offset giving a root its own pattern, for group members and
+/// [`LineBoil::auto_seed`](crate::LineBoil::auto_seed).
+pub(crate) fn root_seed_offset(root: Entity) -> f32 {
+    (root.index_u32() % GROUP_SEED_WRAP) as f32
+}
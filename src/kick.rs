@@ -0,0 +1,93 @@
+//! One-drawing intensity spikes for impact feedback.
+
+use bevy::prelude::*;
+
+use crate::{
+    LineBoil, LineBoilClock, LineBoilDrive, LineBoilDriveMapping, LineBoilGroup,
+    clock::LineBoilOwnTime, group,
+};
+
+/// Spikes the boil of a [`LineBoil`] root for a single held frame, then reverts.
+///
+/// Insert it on the root (or use
+/// [`LineBoilCommandsExt::kick_boil`](crate::LineBoilCommandsExt::kick_boil)) when a hit
+/// lands. From the next rendered frame until the current held frame ends, the effective
+/// intensity is [`intensity`](Self::intensity), skipping the active window, drive and
+/// [`max_intensity_rate`](crate::LineBoilConfig::max_intensity_rate) easing, so the drawing on
+/// screen snaps to one exaggerated version of itself. Normal boil resumes on the next held
+/// frame and the component is removed. [`LineBoilGlobalScale`](crate::LineBoilGlobalScale)
+/// still applies.
+///
+/// Unlike ramping [`LineBoilDrive`](crate::LineBoilDrive) up and down, the kick has no decay:
+/// it is one distorted drawing. Inserting it again restarts it.
+#[derive(Component, Clone, Debug)]
+pub struct LineBoilKick {
+    /// Intensity of the kicked drawing, in normalized device coordinates.
+    pub intensity: f32,
+    /// Held frame the kick is shown on, fixed when it is first seen.
+    held_frame: Option<f32>,
+    /// Whether the held frame has passed. The kick is removed once it has been reverted.
+    pub(crate) ended: bool,
+}
+
+impl LineBoilKick {
+    /// Creates a kick spiking the intensity to `intensity`.
+    pub fn new(intensity: f32) -> Self {
+        Self {
+            intensity,
+            held_frame: None,
+            ended: false,
+        }
+    }
+}
+
+/// Pins new kicks to the current held frame, ends those whose frame has passed and removes
+/// kicks that were reverted on the previous frame.
+///
+/// The held frame is the one the shader draws: from the root's own time (or its group's
+/// beat) shifted by [`time_offset`](crate::LineBoilSettings::time_offset), at the frame rate
+/// after drives and groups.
+#[allow(clippy::type_complexity)]
+pub(crate) fn tick_line_boil_kicks(
+    mut commands: Commands,
+    clock: Res<LineBoilClock>,
+    mut kick_query: Query<(
+        Entity,
+        &mut LineBoilKick,
+        &LineBoil,
+        Option<&LineBoilDrive>,
+        Option<&LineBoilDriveMapping>,
+        Option<&LineBoilGroup>,
+        Option<&LineBoilOwnTime>,
+    )>,
+    root_query: Query<(Entity, &LineBoil, &LineBoilGroup, Option<&LineBoilOwnTime>)>,
+) {
+    let beats = group::group_beats(root_query.iter().map(
+        |(root, line_boil, group, own_time)| {
+            let time = LineBoilOwnTime::or_clock(own_time, &clock);
+            (root, *group, time, line_boil.settings.frame_rate)
+        },
+    ));
+
+    for (entity, mut kick, line_boil, drive, drive_mapping, group, own_time) in kick_query.iter_mut() {
+        if kick.ended {
+            commands.entity(entity).remove::<LineBoilKick>();
+            continue;
+        }
+        let mut driven = line_boil.settings;
+        if let Some(drive) = drive {
+            drive.apply(drive_mapping, &mut driven);
+        }
+        let mut time = LineBoilOwnTime::or_clock(own_time, &clock);
+        if let Some(beat) = group.and_then(|group| beats.get(group)) {
+            time = beat.time;
+            driven.frame_rate = beat.frame_rate;
+        }
+        let held_frame = ((time + driven.time_offset) * driven.frame_rate).floor();
+        match kick.held_frame {
+            None => kick.held_frame = Some(held_frame),
+            Some(kicked_frame) if kicked_frame != held_frame => kick.ended = true,
+            Some(_) => {}
+        }
+    }
+}
@@ -12,7 +12,7 @@
 //! fn main() {
 //!     App::new()
 //!         .add_plugins(DefaultPlugins)
-//!         .add_plugins(LineBoilPlugin)
+//!         .add_plugins(LineBoilPlugin::default().with_mesh_budget(64))
 //!         .add_systems(Startup, setup)
 //!         .run();
 //! }
@@ -21,160 +21,1348 @@
 //!     // Spawn a model with line boil effect
 //!     commands.spawn((
 //!         SceneRoot(asset_server.load("model.glb#Scene0")),
-//!         LineBoil,
+//!         LineBoil::subtle(),
 //!     ));
 //! }
 //! ```
+//!
+//! To place gameplay elements on the displacement itself, [`boil_noise`] samples the
+//! same noise field the shader uses on the CPU.
+//!
+//! To stylize whole asset categories without per-spawn code, insert a
+//! [`LineBoilSceneRules`] resource mapping asset path prefixes to [`LineBoil`] values.
+//!
+//! To author the boil in a DCC tool instead, tag glTF nodes with extras such as
+//! `{"line_boil": "aggressive"}`; the named preset is applied to that node's subtree.
+//!
+//! For a cheaper boil in secondary views such as a minimap, add a [`LineBoilViewVariant`]
+//! and render its layer with that camera.
+//!
+//! To composite the boiled silhouette in post (paper textures, halftone, ...), add a
+//! [`LineBoilPass`] next to [`LineBoil`] and render its layer into a texture.
+//!
+//! To get the displaced vertex positions back on the CPU, spawn a [`LineBoilReadback`] and
+//! observe [`LineBoilReadbackComplete`].
+
+mod adapter;
+mod area;
+mod bounds;
+mod clock;
+mod code;
+mod commands;
+mod crease;
+mod drive;
+mod gltf_extras;
+mod global_scale;
+mod group;
+mod kick;
+mod mask;
+mod material;
+mod mesh2d;
+mod noise;
+mod pass;
+mod path;
+mod readback;
+mod scene_rules;
+mod view_variant;
+
+pub use adapter::{LineBoilAdapterFallback, LineBoilAdapterFilter};
+pub use area::{ATTRIBUTE_BOIL_AREA, ATTRIBUTE_BOIL_AREA_LOCATION};
+pub use bounds::{displaced_aabb, world_per_ndc};
+pub use clock::{LineBoilClock, LineBoilTimeMode};
+pub use code::LineBoilCodeError;
+pub use commands::LineBoilCommandsExt;
+pub use crease::{ATTRIBUTE_BOIL_CREASE, ATTRIBUTE_BOIL_CREASE_LOCATION};
+pub use drive::{LineBoilDrive, LineBoilDriveMapping};
+pub use gltf_extras::GLTF_EXTRAS_KEY;
+pub use global_scale::LineBoilGlobalScale;
+pub use group::LineBoilGroup;
+pub use kick::LineBoilKick;
+pub use mask::{ATTRIBUTE_BOIL_MASK, ATTRIBUTE_BOIL_MASK_LOCATION, MaskAttribute};
+pub use material::{
+    DisplacementSpace, LineBoilMaterial, LineBoilMaterialKey, LineBoilSettings, MirrorAxis,
+    NoiseKind,
+};
+pub use mesh2d::{LineBoil2dPlugin, LineBoilMaterial2d};
+pub use noise::boil_noise;
+pub use pass::{LineBoilPass, LineBoilPassCopy};
+pub use path::LineBoilPath;
+pub use readback::{LineBoilReadback, LineBoilReadbackComplete};
+pub use scene_rules::LineBoilSceneRules;
+pub use view_variant::{LineBoilViewCopy, LineBoilViewVariant};
+
+use clock::LineBoilOwnTime;
+use gltf_extras::LineBoilFromExtras;
+use view_variant::LineBoilViewCopySettings;
+
+use std::{
+    any::{Any, TypeId},
+    hash::Hash,
+    marker::PhantomData,
+    ops::Deref,
+};
 
 use bevy::{
     asset::{load_internal_asset, uuid_handle},
-    pbr::{ExtendedMaterial, MaterialExtension},
+    mesh::{InheritWeightSystems, MeshTag},
+    pbr::ExtendedMaterial,
+    platform::collections::{HashMap, HashSet},
     prelude::*,
-    render::render_resource::AsBindGroup,
-    shader::ShaderRef,
+    scene::SceneInstanceReady,
 };
 
 /// Shader handle for the line boil vertex shader
 pub const LINE_BOIL_SHADER_HANDLE: Handle<Shader> =
     uuid_handle!("89237458-9234-4589-a3ab-cdef12345678");
 
+/// Shader handle for the line boil fragment shader, used for
+/// [`LineBoilSettings::detail_normal_strength`]
+pub const LINE_BOIL_FRAGMENT_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("89237458-9234-4589-a3ab-cdef12345679");
+
+/// Shader handle for the line boil prepass vertex shader, used by camera prepasses and
+/// [`LineBoilSettings::boil_shadows`]
+pub const LINE_BOIL_PREPASS_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("89237458-9234-4589-a3ab-cdef1234567b");
+
+/// Shader handle for the compute shader behind [`LineBoilReadback`]
+pub const LINE_BOIL_READBACK_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("89237458-9234-4589-a3ab-cdef1234567c");
+
+/// Shader handle for the `bevy_line_boil::common` import shared by the line boil shaders
+pub const LINE_BOIL_COMMON_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("89237458-9234-4589-a3ab-cdef1234567a");
+
+/// Shader handle for the line boil vertex shader of 2D meshes, used by [`LineBoil2dPlugin`]
+pub const LINE_BOIL_2D_SHADER_HANDLE: Handle<Shader> =
+    uuid_handle!("89237458-9234-4589-a3ab-cdef1234567d");
+
 /// Plugin that adds line boil effect support.
 ///
 /// Add this plugin to your app, then add the [`LineBoil`] component to any entity
 /// with a glTF scene to apply the effect to all its meshes.
-pub struct LineBoilPlugin;
+///
+/// All plugin-level configuration goes through its builder methods:
+///
+/// ```rust,ignore
+/// app.add_plugins(
+///     LineBoilPlugin::default()
+///         .with_default_settings(LineBoilSettings::AGGRESSIVE)
+///         .with_mesh_budget(64)
+///         .with_scene_rules(LineBoilSceneRules::default().with_rule("characters", LineBoil::new())),
+/// );
+/// ```
+///
+/// `add_plugins(LineBoilPlugin)` keeps working and is equivalent to `LineBoilPlugin::default()`.
+///
+/// The plugin converts meshes using [`StandardMaterial`]. For meshes with other materials
+/// (a toon material, or an `ExtendedMaterial<StandardMaterial, MyExtension>`), add it for
+/// that material type as well. The WGSL only displaces vertex positions, so any material
+/// using Bevy's PBR vertex output works, as long as its own bindings don't collide with the
+/// line boil uniform at binding 100:
+///
+/// ```rust,ignore
+/// app.add_plugins((LineBoilPlugin::default(), LineBoilPlugin::<ToonMaterial>::default()));
+/// ```
+///
+/// Plugin-level options are shared by all material types and taken from the first
+/// `LineBoilPlugin` added.
+#[derive(Clone)]
+pub struct LineBoilPlugin<M = StandardMaterial> {
+    config: LineBoilConfig,
+    scene_rules: Option<LineBoilSceneRules>,
+    marker: PhantomData<fn() -> M>,
+}
+
+/// The default [`LineBoilPlugin`], so the plugin can still be added as if it were a unit struct.
+#[allow(non_upper_case_globals)]
+pub const LineBoilPlugin: LineBoilPlugin = LineBoilPlugin::DEFAULT;
+
+impl<M: LineBoilBaseMaterial> LineBoilPlugin<M> {
+    const DEFAULT: Self = Self {
+        config: LineBoilConfig {
+            default_settings: LineBoilSettings::SUBTLE,
+            mesh_budget: None,
+            max_intensity_rate: None,
+            adapter_denylist: Vec::new(),
+            auto_seed: true,
+        },
+        scene_rules: None,
+        marker: PhantomData,
+    };
+
+    /// Sets the settings used by [`LineBoil::new`] / [`LineBoil::default`].
+    pub fn with_default_settings(mut self, settings: LineBoilSettings) -> Self {
+        self.config.default_settings = settings;
+        self
+    }
+
+    /// Sets whether [`LineBoil`]s that don't choose themselves get a per-entity seed offset,
+    /// see [`LineBoil::auto_seed`]. On by default.
+    pub fn with_auto_seed(mut self, auto_seed: bool) -> Self {
+        self.config.auto_seed = auto_seed;
+        self
+    }
+
+    /// Limits how many meshes are converted per frame, spreading the cost of boiling large
+    /// scenes over several frames. Unlimited by default.
+    pub fn with_mesh_budget(mut self, mesh_budget: usize) -> Self {
+        self.config.mesh_budget = Some(mesh_budget);
+        self
+    }
+
+    /// Limits how fast the effective intensity may change, in intensity units per second, so
+    /// abrupt changes from drives or active windows ease into the geometry instead of popping.
+    /// Unlimited by default.
+    pub fn with_max_intensity_rate(mut self, max_intensity_rate: f32) -> Self {
+        self.config.max_intensity_rate = Some(max_intensity_rate);
+        self
+    }
 
-impl Plugin for LineBoilPlugin {
+    /// Disables the boil on GPU adapters matching any of `filters`, for low-end or software
+    /// adapters where it is too costly or fails to compile. On a match, a
+    /// [`LineBoilAdapterFallback`] is inserted at startup and meshes keep their plain
+    /// materials. Empty by default.
+    ///
+    /// ```rust,ignore
+    /// LineBoilPlugin::default().with_adapter_denylist([
+    ///     LineBoilAdapterFilter::Software,
+    ///     LineBoilAdapterFilter::Name("Mali-G52".into()),
+    /// ])
+    /// ```
+    pub fn with_adapter_denylist(
+        mut self,
+        filters: impl IntoIterator<Item = LineBoilAdapterFilter>,
+    ) -> Self {
+        self.config.adapter_denylist.extend(filters);
+        self
+    }
+
+    /// Inserts [`LineBoilSceneRules`] applying [`LineBoil`] to scenes by asset path.
+    pub fn with_scene_rules(mut self, scene_rules: LineBoilSceneRules) -> Self {
+        self.scene_rules = Some(scene_rules);
+        self
+    }
+}
+
+impl<M: LineBoilBaseMaterial> Default for LineBoilPlugin<M> {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl LineBoilPlugin {
+    /// Returns the default plugin for [`StandardMaterial`], so `LineBoilPlugin::default()`
+    /// needs no type annotation.
+    #[allow(clippy::should_implement_trait)]
+    pub fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl<M: LineBoilBaseMaterial> Plugin for LineBoilPlugin<M> {
     fn build(&self, app: &mut App) {
-        app.add_plugins(
-            MaterialPlugin::<ExtendedMaterial<StandardMaterial, LineBoilMaterial>>::default(),
+        if !app.is_plugin_added::<LineBoilCorePlugin>() {
+            app.add_plugins(LineBoilCorePlugin {
+                config: self.config.clone(),
+                scene_rules: self.scene_rules.clone(),
+            });
+        }
+        // The core plugin already handles `StandardMaterial`-based materials, which pass
+        // copies use for every base material.
+        if TypeId::of::<M>() != TypeId::of::<StandardMaterial>() {
+            add_line_boil_material::<M>(app);
+        }
+
+        add_line_boil_conversion::<LineBoilMesh3d<M>>(app);
+        app.add_systems(
+            Update,
+            (
+                pass::spawn_line_boil_pass_copies::<M>,
+                view_variant::spawn_line_boil_view_copies::<M>,
+            )
+                .in_set(LineBoilSystems::Copy),
         );
+    }
+}
+
+/// Base materials [`LineBoilPlugin`] can wrap: any [`Material`] whose
+/// [`ExtendedMaterial`] with [`LineBoilMaterial`] can be rendered. Implemented automatically.
+pub trait LineBoilBaseMaterial: Material<Data: PartialEq + Eq + Hash + Copy> {}
+
+impl<M: Material<Data: PartialEq + Eq + Hash + Copy>> LineBoilBaseMaterial for M {}
+
+/// A kind of mesh the plugin converts: the component holding its material before and after
+/// conversion, and how the line boil material is built from the original one.
+pub(crate) trait LineBoilTarget: Send + Sync + 'static {
+    /// The material meshes have before conversion.
+    type Base: Asset + Clone;
+    /// The material replacing `Base` on converted meshes.
+    type Boiled: LineBoilAsset;
+    /// Component holding a mesh's `Base` material.
+    type Source: Component + Deref<Target = Handle<Self::Base>> + From<Handle<Self::Base>>;
+    /// Component holding a converted mesh's `Boiled` material.
+    type Converted: Component
+        + Deref<Target = Handle<Self::Boiled>>
+        + From<Handle<Self::Boiled>>;
+
+    /// Wraps `base` with the displacement of `extension`.
+    fn boil(base: &Self::Base, extension: LineBoilMaterial) -> Self::Boiled;
+}
+
+/// [`LineBoilTarget`] for [`Mesh3d`]s with base material `M`, converted to
+/// `ExtendedMaterial<M, LineBoilMaterial>`.
+pub(crate) struct LineBoilMesh3d<M>(PhantomData<fn() -> M>);
+
+impl<M: LineBoilBaseMaterial> LineBoilTarget for LineBoilMesh3d<M> {
+    type Base = M;
+    type Boiled = ExtendedMaterial<M, LineBoilMaterial>;
+    type Source = MeshMaterial3d<M>;
+    type Converted = MeshMaterial3d<ExtendedMaterial<M, LineBoilMaterial>>;
+
+    fn boil(base: &M, extension: LineBoilMaterial) -> Self::Boiled {
+        ExtendedMaterial {
+            base: base.clone(),
+            extension: extension.on_base::<M>(),
+        }
+    }
+}
+
+/// A material asset carrying a [`LineBoilMaterial`], whose settings and per-frame values the
+/// plugin keeps up to date.
+pub(crate) trait LineBoilAsset: Asset {
+    fn line_boil(&self) -> &LineBoilMaterial;
+
+    fn line_boil_mut(&mut self) -> &mut LineBoilMaterial;
+
+    /// How brightly the material glows, from 0 to 1, for
+    /// [`LineBoilSettings::emissive_boil`].
+    fn glow(&self) -> f32 {
+        0.0
+    }
+}
+
+impl<B: Material> LineBoilAsset for ExtendedMaterial<B, LineBoilMaterial> {
+    fn line_boil(&self) -> &LineBoilMaterial {
+        &self.extension
+    }
+
+    fn line_boil_mut(&mut self) -> &mut LineBoilMaterial {
+        &mut self.extension
+    }
+
+    fn glow(&self) -> f32 {
+        // Only `StandardMaterial` bases are known to glow.
+        (&self.base as &dyn Any)
+            .downcast_ref::<StandardMaterial>()
+            .map_or(0.0, |base| base.emissive.luminance().min(1.0))
+    }
+}
+
+/// Ordering of the plugin's systems within [`Update`].
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+enum LineBoilSystems {
+    /// Wraps the materials of meshes under [`LineBoil`] roots.
+    Convert,
+    /// Spawns pass and view copies of newly converted meshes.
+    Copy,
+    /// Writes settings and per-frame values into the materials.
+    Upload,
+}
+
+/// Everything shared by the [`LineBoilPlugin`]s of all base materials, plus the
+/// `StandardMaterial`-based material.
+struct LineBoilCorePlugin {
+    config: LineBoilConfig,
+    scene_rules: Option<LineBoilSceneRules>,
+}
+
+impl Plugin for LineBoilCorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone())
+            .init_resource::<LineBoilClock>()
+            .init_resource::<LineBoilGlobalScale>()
+            .register_type::<LineBoil>()
+            .register_type::<LineBoilSettings>()
+            .register_type::<LineBoilMaterial>();
+        if let Some(scene_rules) = &self.scene_rules {
+            app.insert_resource(scene_rules.clone());
+        }
 
+        load_internal_asset!(
+            app,
+            LINE_BOIL_COMMON_SHADER_HANDLE,
+            "line_boil_common.wgsl",
+            |source, path| Shader {
+                // Picked up by every shader importing it.
+                shader_defs: noise::noise_shader_defs(),
+                ..Shader::from_wgsl(source, path)
+            }
+        );
         load_internal_asset!(
             app,
             LINE_BOIL_SHADER_HANDLE,
             "line_boil.wgsl",
             Shader::from_wgsl
         );
+        load_internal_asset!(
+            app,
+            LINE_BOIL_PREPASS_SHADER_HANDLE,
+            "line_boil_prepass.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            LINE_BOIL_FRAGMENT_SHADER_HANDLE,
+            "line_boil_fragment.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            LINE_BOIL_READBACK_SHADER_HANDLE,
+            "line_boil_readback.wgsl",
+            Shader::from_wgsl
+        );
+        app.add_plugins(readback::LineBoilReadbackPlugin);
+        add_line_boil_material::<StandardMaterial>(app);
 
+        app.configure_sets(
+            Update,
+            (
+                LineBoilSystems::Convert,
+                LineBoilSystems::Copy,
+                LineBoilSystems::Upload,
+            )
+                .chain(),
+        );
         app.add_systems(
             Update,
             (
-                apply_line_boil_to_marked_entities,
-                cleanup_old_materials.after(apply_line_boil_to_marked_entities),
+                scene_rules::apply_line_boil_scene_rules
+                    .run_if(resource_exists::<LineBoilSceneRules>)
+                    .before(LineBoilSystems::Convert),
+                gltf_extras::apply_line_boil_gltf_extras.before(LineBoilSystems::Convert),
+                warn_nested_line_boil,
+                // Sees roots the rules insert this frame, before their first conversion.
+                resolve_default_settings
+                    .after(scene_rules::apply_line_boil_scene_rules)
+                    .after(gltf_extras::apply_line_boil_gltf_extras)
+                    .before(LineBoilSystems::Convert),
+                clock::tick_line_boil_clock.before(LineBoilSystems::Upload),
+                // Reads whether the clock was set before its tick clears it.
+                clock::tick_line_boil_own_time
+                    .before(clock::tick_line_boil_clock)
+                    .before(LineBoilSystems::Upload),
+                kick::tick_line_boil_kicks
+                    .after(clock::tick_line_boil_clock)
+                    .after(clock::tick_line_boil_own_time)
+                    .before(LineBoilSystems::Upload),
+                area::insert_line_boil_area_attribute.after(LineBoilSystems::Convert),
+                crease::insert_line_boil_crease_attribute.after(LineBoilSystems::Convert),
             ),
         );
+        app.add_systems(
+            PostUpdate,
+            view_variant::sync_line_boil_view_copy_weights.after(InheritWeightSystems),
+        );
+    }
+
+    fn finish(&self, app: &mut App) {
+        // The render adapter is known once the render plugin has finished.
+        adapter::check_line_boil_adapter(app);
     }
 }
 
-/// The line boil material extension.
-///
-/// No uniforms — settings are hardcoded as shader constants for WebGL compatibility
-/// and better performance (constant folding).
-#[derive(Asset, AsBindGroup, TypePath, Debug, Clone, Default)]
-pub struct LineBoilMaterial {}
+/// Registers `ExtendedMaterial<B, LineBoilMaterial>` and the systems keeping its assets up
+/// to date.
+fn add_line_boil_material<B: LineBoilBaseMaterial>(app: &mut App) {
+    app.add_plugins(MaterialPlugin::<ExtendedMaterial<B, LineBoilMaterial>>::default());
+    app.add_systems(
+        Update,
+        (
+            sync_line_boil_settings::<LineBoilMesh3d<B>>,
+            update_line_boil_time::<LineBoilMesh3d<B>>,
+            readback::start_line_boil_readbacks::<B>,
+        )
+            .chain()
+            .in_set(LineBoilSystems::Upload),
+    );
+}
 
-impl MaterialExtension for LineBoilMaterial {
-    fn vertex_shader() -> ShaderRef {
-        ShaderRef::Handle(LINE_BOIL_SHADER_HANDLE)
-    }
+/// Adds the systems converting meshes of kind `T` under [`LineBoil`] roots, and restoring
+/// them when the [`LineBoil`] is removed.
+fn add_line_boil_conversion<T: LineBoilTarget>(app: &mut App) {
+    app.init_resource::<LineBoilMaterialCache<T>>();
+    app.add_observer(restore_line_boil_materials::<T>);
+    app.add_observer(reopen_line_boil_scene::<T>);
+    app.add_systems(
+        Update,
+        (
+            (
+                reopen_line_boil_roots::<T>,
+                apply_line_boil_to_marked_entities::<T>
+                    .run_if(not(resource_exists::<LineBoilAdapterFallback>)),
+            )
+                .chain()
+                .in_set(LineBoilSystems::Convert),
+            cleanup_old_materials::<T>.in_set(LineBoilSystems::Copy),
+        ),
+    );
+}
 
-    fn fragment_shader() -> ShaderRef {
-        ShaderRef::Default
-    }
+/// Plugin-level configuration, inserted by [`LineBoilPlugin`] and editable at runtime.
+#[derive(Resource, Clone, Debug)]
+pub struct LineBoilConfig {
+    /// Settings given to a [`LineBoil`] added with [`LineBoil::new`] / [`LineBoil::default`].
+    pub default_settings: LineBoilSettings,
+    /// Maximum number of meshes converted per frame, or `None` for no limit.
+    pub mesh_budget: Option<usize>,
+    /// Maximum change of the effective intensity per second, or `None` for no limit.
+    pub max_intensity_rate: Option<f32>,
+    /// GPU adapters the boil is disabled on. Only checked at startup.
+    pub adapter_denylist: Vec<LineBoilAdapterFilter>,
+    /// Whether [`LineBoil`]s with [`auto_seed`](LineBoil::auto_seed) left at `None` get a
+    /// per-entity seed offset. Only read when a [`LineBoil`] is added.
+    pub auto_seed: bool,
 }
 
-/// Marker component to apply line boil effect to an entity and its mesh children.
+/// Component to apply line boil effect to an entity and its mesh children.
 ///
 /// Add this component to an entity (typically a glTF scene root) to apply the
-/// line boil effect to all meshes within its hierarchy.
+/// line boil effect to all meshes within its hierarchy. A `LineBoil` nested inside
+/// another one takes over its own subtree; meshes are never boiled twice, and a
+/// warning is logged since this is usually unintended.
 ///
 /// # Example
 ///
 /// ```rust,ignore
 /// commands.spawn((
 ///     SceneRoot(asset_server.load("character.glb#Scene0")),
-///     LineBoil,
+///     LineBoil::aggressive().with_seed(42.0),
 /// ));
 /// ```
-#[derive(Component, Default, Clone)]
-pub struct LineBoil;
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component, Default)]
+pub struct LineBoil {
+    /// Effect parameters copied into every converted material, and again whenever they
+    /// change. Switching [`LineBoilSettings::per_instance_seed`] only affects meshes
+    /// converted afterwards, as it decides how materials are shared.
+    pub settings: LineBoilSettings,
+    /// Time window `(start, end)` in seconds, relative to when the first mesh was
+    /// converted, outside of which the boil holds rigid. `None` boils forever.
+    pub active_window: Option<(f32, f32)>,
+    /// Rate at which this root's boil time advances relative to real time, replacing the
+    /// global scale of [`LineBoilClock`] (which follows virtual [`Time`], so slow motion
+    /// slows it). E.g. `Some(1.0)` keeps a clock prop boiling in real time during bullet
+    /// time. `None` follows the global clock. Setting the clock (e.g.
+    /// [`set_held_frame`](LineBoilClock::set_held_frame) or the first
+    /// [authoritative](LineBoilClock::set_authoritative) time) still moves this root to the
+    /// new time, from where it advances at its own rate.
+    pub time_scale_override: Option<f32>,
+    /// How many levels below this entity conversion walks, e.g. `Some(0)` only converts the
+    /// entity's own mesh. `None` walks the whole hierarchy. Subtrees can also be excluded
+    /// with [`LineBoilStop`].
+    pub max_depth: Option<usize>,
+    /// When set, `settings` is replaced by [`LineBoilConfig::default_settings`] once the
+    /// component is added. Set by [`new`](Self::new) / [`default`](Self::default), cleared
+    /// by the presets and every settings builder.
+    pub use_default_settings: bool,
+    /// Whether the seed is offset by a value derived from this entity, so copies of the same
+    /// model spawned with the same settings don't boil in lockstep. `None` (from
+    /// [`new`](Self::new), [`default`](Self::default) and the presets) is replaced by
+    /// [`LineBoilConfig::auto_seed`] once the component is added;
+    /// [`with_seed`](Self::with_seed) sets `Some(false)`, so an explicit seed is used
+    /// exactly. Members of a [`LineBoilGroup`] always get their own offset. See
+    /// [`effective_seed`](Self::effective_seed) for the resulting seed.
+    pub auto_seed: Option<bool>,
+}
+
+impl Default for LineBoil {
+    fn default() -> Self {
+        Self {
+            settings: LineBoilSettings::default(),
+            active_window: None,
+            time_scale_override: None,
+            max_depth: None,
+            use_default_settings: true,
+            auto_seed: None,
+        }
+    }
+}
+
+impl LineBoil {
+    /// Creates a line boil using the plugin's [default settings](LineBoilPlugin::with_default_settings).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Very gentle wobble (intensity=0.008, frame_rate=8, noise=6).
+    pub fn subtle() -> Self {
+        Self {
+            settings: LineBoilSettings::SUBTLE,
+            active_window: None,
+            time_scale_override: None,
+            max_depth: None,
+            use_default_settings: false,
+            auto_seed: None,
+        }
+    }
+
+    /// More pronounced effect (intensity=0.04, frame_rate=4, noise=12).
+    pub fn aggressive() -> Self {
+        Self {
+            settings: LineBoilSettings::AGGRESSIVE,
+            active_window: None,
+            time_scale_override: None,
+            max_depth: None,
+            use_default_settings: false,
+            auto_seed: None,
+        }
+    }
+
+    /// Returns the preset called `name` (`"subtle"` or `"aggressive"`), e.g. as named in
+    /// glTF extras.
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "subtle" => Some(Self::subtle()),
+            "aggressive" => Some(Self::aggressive()),
+            _ => None,
+        }
+    }
+
+    /// Sets how far vertices move.
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.settings_mut().intensity = intensity;
+        self
+    }
+
+    /// Sets the FPS of the time quantization (lower = more "held").
+    pub fn with_frame_rate(mut self, frame_rate: f32) -> Self {
+        self.settings_mut().frame_rate = frame_rate;
+        self
+    }
+
+    /// Sets how much of each held frame eases into the next one (0 = hard snap).
+    pub fn with_frame_blend(mut self, frame_blend: f32) -> Self {
+        self.settings_mut().frame_blend = frame_blend;
+        self
+    }
+
+    /// Shifts when held frames land by `time_offset` seconds, keeping the pattern.
+    pub fn with_time_offset(mut self, time_offset: f32) -> Self {
+        self.settings_mut().time_offset = time_offset;
+        self
+    }
+
+    /// Sets the turbulence scale.
+    pub fn with_noise_frequency(mut self, noise_frequency: f32) -> Self {
+        self.settings_mut().noise_frequency = noise_frequency;
+        self
+    }
+
+    /// Sets the noise function of the turbulence.
+    pub fn with_noise_kind(mut self, noise_kind: NoiseKind) -> Self {
+        self.settings_mut().noise_kind = noise_kind;
+        self
+    }
+
+    /// Sets the turbulence scale separately along the object's local X, Y and Z axes, for
+    /// grain-aligned boil (zero = use [`with_noise_frequency`](Self::with_noise_frequency)).
+    pub fn with_noise_frequency_axes(mut self, noise_frequency_axes: Vec3) -> Self {
+        self.settings_mut().noise_frequency_axes = noise_frequency_axes;
+        self
+    }
+
+    /// Lets the boil's energy ebb and flow across the surface: intensity is modulated by up
+    /// to `± strength` by a slowly drifting noise of the given spatial `frequency`
+    /// (0 strength = uniform).
+    pub fn with_intensity_noise(mut self, strength: f32, frequency: f32) -> Self {
+        let settings = self.settings_mut();
+        settings.intensity_noise_strength = strength;
+        settings.intensity_noise_frequency = frequency;
+        self
+    }
+
+    /// Fades the boil out with view depth, from full intensity at `start` to none at `end`
+    /// world units from the camera.
+    pub fn with_distance_falloff(mut self, start: f32, end: f32) -> Self {
+        let settings = self.settings_mut();
+        settings.falloff_start = start;
+        settings.falloff_end = end;
+        self
+    }
+
+    /// Limits the boil to the object-space box from `min` to `max`, fading out over a soft
+    /// edge `margin` wide centred on its boundary.
+    pub fn with_region(mut self, min: Vec3, max: Vec3, margin: f32) -> Self {
+        let settings = self.settings_mut();
+        settings.region_min = min;
+        settings.region_max = max;
+        settings.region_margin = margin;
+        self
+    }
+
+    /// Sets whether the boil wobbles across the screen (the default) or is displaced in
+    /// world units, attached to the object.
+    pub fn with_displacement_space(mut self, displacement_space: DisplacementSpace) -> Self {
+        self.settings_mut().displacement_space = displacement_space;
+        self
+    }
+
+    /// Sets the noise seed, for variation between entities, and turns off
+    /// [`auto_seed`](Self::auto_seed) so it is used exactly.
+    pub fn with_seed(mut self, seed: f32) -> Self {
+        self.settings_mut().seed = seed;
+        self.auto_seed = Some(false);
+        self
+    }
+
+    /// Sets whether the seed is offset per entity, overriding the plugin's default, see
+    /// [`auto_seed`](Self::auto_seed).
+    pub fn with_auto_seed(mut self, auto_seed: bool) -> Self {
+        self.auto_seed = Some(auto_seed);
+        self
+    }
+
+    /// Returns the seed the materials of root `entity` boil with on the GPU: the settings'
+    /// [`seed`](LineBoilSettings::seed) plus the per-entity offset, if
+    /// [`auto_seed`](Self::auto_seed) or membership of `group` gives it one. Pass settings
+    /// with this seed to [`boil_noise`] to sample the same pattern as the GPU. Meshes with
+    /// [`per_instance_seed`](LineBoilSettings::per_instance_seed) add their own instance
+    /// offset on top.
+    pub fn effective_seed(&self, entity: Entity, group: Option<&LineBoilGroup>) -> f32 {
+        self.settings.seed + self.seed_offset(entity, group.is_some())
+    }
+
+    /// Returns the per-entity seed offset of root `entity`.
+    fn seed_offset(&self, entity: Entity, grouped: bool) -> f32 {
+        if grouped || self.auto_seed.unwrap_or(true) {
+            group::root_seed_offset(entity)
+        } else {
+            0.0
+        }
+    }
+
+    /// Sets how strongly intensity scales with local triangle size (0 = uniform).
+    pub fn with_area_response(mut self, area_response: f32) -> Self {
+        self.settings_mut().area_response = area_response;
+        self
+    }
+
+    /// Treats each child of this root as a crowd member sharing materials with the others,
+    /// boiling with its own seed (see [`LineBoilSettings::per_instance_seed`]).
+    pub fn with_per_instance_seed(mut self, per_instance_seed: bool) -> Self {
+        self.settings_mut().per_instance_seed = per_instance_seed;
+        self
+    }
+
+    /// Mirrors the boil across an object-space axis, for symmetric models.
+    pub fn with_mirror_axis(mut self, axis: MirrorAxis) -> Self {
+        self.settings_mut().mirror_axis = Some(axis);
+        self
+    }
+
+    /// Shakes the whole object as a flat unit on screen instead of per vertex.
+    pub fn with_clip_space_shake(mut self, clip_space_shake: bool) -> Self {
+        self.settings_mut().clip_space_shake = clip_space_shake;
+        self
+    }
+
+    /// Sets how fast the seed drifts over time (0 = fixed seed).
+    pub fn with_seed_drift(mut self, seed_drift: f32) -> Self {
+        self.settings_mut().seed_drift = seed_drift;
+        self
+    }
+
+    /// Returns the settings for modification, opting out of the plugin's default settings.
+    fn settings_mut(&mut self) -> &mut LineBoilSettings {
+        self.use_default_settings = false;
+        &mut self.settings
+    }
+
+    /// Leans the whole object `strength` world units along the world-space `direction`,
+    /// on top of the boil.
+    pub fn with_bias(mut self, direction: Vec3, strength: f32) -> Self {
+        let settings = self.settings_mut();
+        settings.bias_direction = direction;
+        settings.bias_strength = strength;
+        self
+    }
+
+    /// Sets how strongly hard edges hold still (0 = boil uniformly, 1 = rigid creases).
+    pub fn with_crease_preservation(mut self, crease_preservation: f32) -> Self {
+        self.settings_mut().crease_preservation = crease_preservation;
+        self
+    }
+
+    /// Scales displacement per vertex by `attribute`, e.g. to keep painted feet planted.
+    pub fn with_mask_attribute(mut self, attribute: MaskAttribute) -> Self {
+        self.settings_mut().mask_attribute = Some(attribute);
+        self
+    }
+
+    /// Samples double-sided sheets of the given thickness on their midsurface, so both
+    /// sides boil together (0 = disabled).
+    pub fn with_sheet_thickness(mut self, sheet_thickness: f32) -> Self {
+        self.settings_mut().sheet_thickness = sheet_thickness;
+        self
+    }
+
+    /// Sets the strength of the shading-only pencil grain shimmer (0 = disabled).
+    pub fn with_detail_normal_strength(mut self, detail_normal_strength: f32) -> Self {
+        self.settings_mut().detail_normal_strength = detail_normal_strength;
+        self
+    }
+
+    /// Boils glowing materials harder (by up to `1 + emissive_boil` at full emissive
+    /// brightness) and flickers their glow by `emissive_pulse` on each held frame.
+    pub fn with_emissive_boil(mut self, emissive_boil: f32, emissive_pulse: f32) -> Self {
+        let settings = self.settings_mut();
+        settings.emissive_boil = emissive_boil;
+        settings.emissive_pulse = emissive_pulse;
+        self
+    }
+
+    /// Sets whether lighting follows the displaced surface (off by default).
+    pub fn with_recompute_normals(mut self, recompute_normals: bool) -> Self {
+        self.settings_mut().recompute_normals = recompute_normals;
+        self
+    }
+
+    /// Sets whether shadows boil with the mesh (off by default; prepasses always boil).
+    pub fn with_boil_shadows(mut self, boil_shadows: bool) -> Self {
+        self.settings_mut().boil_shadows = boil_shadows;
+        self
+    }
 
-/// Marker component to track meshes that have already been processed.
+    /// Only boils between `start` and `end` seconds after conversion, then holds rigid.
+    ///
+    /// The intensity eases in and out over [`ACTIVE_WINDOW_EASE`] seconds at each boundary.
+    pub fn with_active_window(mut self, start: f32, end: f32) -> Self {
+        self.active_window = Some((start, end));
+        self
+    }
+
+    /// Advances this root's boil time at `time_scale` × real time, ignoring the global time
+    /// scale.
+    pub fn with_time_scale_override(mut self, time_scale: f32) -> Self {
+        self.time_scale_override = Some(time_scale);
+        self
+    }
+
+    /// Only converts meshes up to `max_depth` levels below this entity.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Returns the intensity multiplier at `elapsed` seconds after conversion.
+    pub fn window_weight(&self, elapsed: f32) -> f32 {
+        let Some((start, end)) = self.active_window else {
+            return 1.0;
+        };
+        if elapsed <= start || elapsed >= end {
+            return 0.0;
+        }
+
+        let ease = ACTIVE_WINDOW_EASE.min((end - start) * 0.5);
+        let ease_in = ((elapsed - start) / ease).min(1.0);
+        let ease_out = ((end - elapsed) / ease).min(1.0);
+        smoothstep(ease_in) * smoothstep(ease_out)
+    }
+}
+
+/// Marker excluding an entity and its descendants from the conversion of the enclosing
+/// [`LineBoil`], and stopping the hierarchy walk there.
+///
+/// Useful to keep e.g. a glowing UI gizmo under a character steady, or to skip deep
+/// non-mesh subtrees such as bone hierarchies in large scenes.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct LineBoilStop;
+
+/// Duration in seconds of the intensity ease at each boundary of [`LineBoil::active_window`].
+pub const ACTIVE_WINDOW_EASE: f32 = 0.25;
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Marks a [`LineBoil`] root whose subtree has no unconverted meshes of base material `M`
+/// left, so it is no longer walked every frame.
+///
+/// It's removed again when the root's scene finishes spawning or a new mesh with an `M`
+/// material appears beneath it, and the subtree is walked once more. Remove it yourself to
+/// force a walk, e.g. after removing a [`LineBoilStop`] or raising
+/// [`max_depth`](LineBoil::max_depth).
+#[derive(Component)]
+pub struct LineBoilComplete<M: Asset = StandardMaterial>(PhantomData<fn() -> M>);
+
+impl<M: Asset> Default for LineBoilComplete<M> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// Component to track meshes that have already been processed.
+#[derive(Component)]
+struct LineBoilApplied {
+    /// The [`LineBoil`] root whose settings drive this mesh.
+    root: Entity,
+}
+
+/// The material a converted mesh had before, restored when its [`LineBoil`] is removed.
+/// Holding the handle keeps the original asset alive while the boil is active.
 #[derive(Component)]
-struct LineBoilApplied;
+struct LineBoilOriginalMaterial<T: LineBoilTarget> {
+    material: Handle<T::Base>,
+    /// Whether the conversion added the mesh's [`MeshTag`], which is then removed as well.
+    added_tag: bool,
+}
+
+/// Line boil materials shared by the converted meshes of each [`LineBoil`] root, keyed by the
+/// root and the source material, so submeshes sharing a material keep sharing one and still
+/// batch.
+///
+/// Roots never share materials: each gets its own time, drives and kicks written into them.
+/// A cached material whose settings no longer match its root's is not reused; the next mesh
+/// converted forks a new one instead.
+#[derive(Resource)]
+#[allow(clippy::type_complexity)]
+struct LineBoilMaterialCache<T: LineBoilTarget>(
+    HashMap<(Entity, AssetId<T::Base>), Handle<T::Boiled>>,
+);
+
+impl<T: LineBoilTarget> Default for LineBoilMaterialCache<T> {
+    fn default() -> Self {
+        Self(HashMap::default())
+    }
+}
+
+/// Elapsed time at which the first mesh under a [`LineBoil`] root was converted.
+#[derive(Component)]
+struct LineBoilStarted(f32);
+
+/// Replaces the settings and seed choice of newly added default [`LineBoil`]s with the
+/// plugin defaults.
+fn resolve_default_settings(
+    config: Res<LineBoilConfig>,
+    mut root_query: Query<&mut LineBoil, Added<LineBoil>>,
+) {
+    for mut line_boil in root_query.iter_mut() {
+        if line_boil.use_default_settings {
+            line_boil.settings = config.default_settings;
+            line_boil.use_default_settings = false;
+        }
+        if line_boil.auto_seed.is_none() {
+            line_boil.auto_seed = Some(config.auto_seed);
+        }
+    }
+}
+
+/// Copies the settings of changed [`LineBoil`]s into the materials of their converted
+/// meshes, in place. Meshes converted later pick up the current settings on conversion.
+#[allow(clippy::type_complexity)]
+fn sync_line_boil_settings<T: LineBoilTarget>(
+    root_query: Query<&LineBoil, Changed<LineBoil>>,
+    mesh_query: Query<(&LineBoilApplied, &T::Converted), Without<LineBoilViewCopySettings>>,
+    mut line_boil_materials: ResMut<Assets<T::Boiled>>,
+) {
+    if root_query.is_empty() {
+        return;
+    }
+    for (applied, mat_handle) in mesh_query.iter() {
+        let Ok(line_boil) = root_query.get(applied.root) else {
+            continue;
+        };
+        // Only touch the asset (and re-upload it) when something actually differs.
+        if line_boil_materials
+            .get(&**mat_handle)
+            .is_some_and(|material| material.line_boil().settings != line_boil.settings)
+            && let Some(material) = line_boil_materials.get_mut(&**mat_handle)
+        {
+            material.line_boil_mut().settings = line_boil.settings;
+        }
+    }
+}
 
-fn cleanup_old_materials(
+#[allow(clippy::type_complexity)]
+fn update_line_boil_time<T: LineBoilTarget>(
+    time: Res<Time>,
+    clock: Res<LineBoilClock>,
+    config: Res<LineBoilConfig>,
+    global_scale: Res<LineBoilGlobalScale>,
+    root_query: Query<(
+        Entity,
+        &LineBoil,
+        Option<&LineBoilStarted>,
+        Option<&LineBoilDrive>,
+        Option<&LineBoilDriveMapping>,
+        Option<&LineBoilKick>,
+        Option<&LineBoilOwnTime>,
+        Option<&LineBoilPath>,
+        Option<&LineBoilGroup>,
+    )>,
+    mesh_query: Query<(
+        &LineBoilApplied,
+        &T::Converted,
+        Option<&LineBoilViewCopySettings>,
+    )>,
+    mut line_boil_materials: ResMut<Assets<T::Boiled>>,
+) {
+    let elapsed = time.elapsed_secs();
+    let global_scale = global_scale.scale();
+    let beats = group::group_beats(root_query.iter().filter_map(
+        |(root, line_boil, _, _, _, _, own_time, _, group)| {
+            let time = LineBoilOwnTime::or_clock(own_time, &clock);
+            group.map(|group| (root, *group, time, line_boil.settings.frame_rate))
+        },
+    ));
+
+    // Meshes of a root share materials, which must only be written once per frame.
+    let mut updated = HashSet::<AssetId<T::Boiled>>::default();
+    for (applied, mat_handle, view_settings) in mesh_query.iter() {
+        if !updated.insert(mat_handle.id()) {
+            continue;
+        }
+        let Ok((root, line_boil, started, drive, drive_mapping, kick, own_time, path, group)) =
+            root_query.get(applied.root)
+        else {
+            continue;
+        };
+        let Some(material) = line_boil_materials.get_mut(&**mat_handle) else {
+            continue;
+        };
+
+        let since_start = started.map_or(0.0, |started| elapsed - started.0);
+        let mut driven = view_settings.map_or(line_boil.settings, |view_settings| view_settings.0);
+        driven.intensity *= line_boil.window_weight(since_start) * global_scale;
+        if let Some(drive) = drive {
+            drive.apply(drive_mapping, &mut driven);
+        }
+        if driven.emissive_boil != 0.0 {
+            driven.intensity *= 1.0 + driven.emissive_boil * material.glow();
+        }
+
+        let runtime = &mut material.line_boil_mut().runtime;
+        runtime.time = LineBoilOwnTime::or_clock(own_time, &clock);
+        runtime.seed_offset = line_boil.seed_offset(root, group.is_some());
+        // Group members redraw on their leader's beat; their seed offset keeps them apart.
+        if let Some(beat) = group.and_then(|group| beats.get(group)) {
+            runtime.time = beat.time;
+            driven.frame_rate = beat.frame_rate;
+        }
+        // The uploaded intensity is last frame's effective value, so settle from there.
+        runtime.intensity = match (kick, config.max_intensity_rate) {
+            (Some(kick), _) if !kick.ended => kick.intensity * global_scale,
+            // Snap back from the kick instead of easing down from the spike.
+            (Some(_), _) => driven.intensity,
+            (None, Some(rate)) => {
+                let max_step = rate * time.delta_secs();
+                let step = (driven.intensity - runtime.intensity).clamp(-max_step, max_step);
+                runtime.intensity + step
+            }
+            (None, None) => driven.intensity,
+        };
+        runtime.path = path.map(Into::into).unwrap_or_default();
+        runtime.frame_rate = driven.frame_rate;
+        runtime.noise_frequency = driven.noise_frequency;
+    }
+}
+
+fn cleanup_old_materials<T: LineBoilTarget>(
     mut commands: Commands,
-    query: Query<Entity, (With<LineBoilApplied>, With<MeshMaterial3d<StandardMaterial>>)>,
+    query: Query<Entity, (With<LineBoilApplied>, With<T::Source>)>,
 ) {
     for entity in query.iter() {
-        commands
-            .entity(entity)
-            .remove::<MeshMaterial3d<StandardMaterial>>();
+        commands.entity(entity).remove::<T::Source>();
     }
 }
 
-fn apply_line_boil_to_marked_entities(
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn apply_line_boil_to_marked_entities<T: LineBoilTarget>(
     mut commands: Commands,
-    root_query: Query<Entity, With<LineBoil>>,
-    children_query: Query<&Children>,
-    mesh_query: Query<
-        (Entity, &MeshMaterial3d<StandardMaterial>),
-        Without<LineBoilApplied>,
+    time: Res<Time>,
+    config: Res<LineBoilConfig>,
+    root_query: Query<
+        (Entity, &LineBoil, Has<LineBoilStarted>),
+        Without<LineBoilComplete<T::Base>>,
     >,
-    standard_materials: Res<Assets<StandardMaterial>>,
-    mut line_boil_materials: ResMut<Assets<ExtendedMaterial<StandardMaterial, LineBoilMaterial>>>,
+    boundary_query: Query<(), Or<(With<LineBoil>, With<LineBoilStop>)>>,
+    children_query: Query<&Children>,
+    mesh_query: Query<(Entity, &T::Source, Has<MeshTag>), Without<LineBoilApplied>>,
+    base_materials: Res<Assets<T::Base>>,
+    mut line_boil_materials: ResMut<Assets<T::Boiled>>,
+    mut material_cache: ResMut<LineBoilMaterialCache<T>>,
 ) {
-    for root_entity in root_query.iter() {
-        traverse_and_replace_materials(
+    // Meshes left over once the budget runs out are picked up on the next frame.
+    let mut budget = config.mesh_budget.unwrap_or(usize::MAX);
+
+    for (root_entity, line_boil, started) in root_query.iter() {
+        if budget == 0 {
+            break;
+        }
+        let mut complete = true;
+        let converted = traverse_and_replace_materials(
+            root_entity,
             root_entity,
+            root_entity,
+            0,
+            line_boil,
+            &mut material_cache,
+            &mut budget,
+            &mut complete,
+            &boundary_query,
             &children_query,
             &mesh_query,
-            &standard_materials,
+            &base_materials,
             &mut line_boil_materials,
             &mut commands,
         );
+
+        if converted && !started {
+            commands
+                .entity(root_entity)
+                .insert(LineBoilStarted(time.elapsed_secs()));
+        }
+        if complete {
+            commands
+                .entity(root_entity)
+                .insert(LineBoilComplete::<T::Base>::default());
+        }
     }
 }
 
-fn traverse_and_replace_materials(
-    entity: Entity,
-    children_query: &Query<&Children>,
-    mesh_query: &Query<
-        (Entity, &MeshMaterial3d<StandardMaterial>),
-        Without<LineBoilApplied>,
+/// Walks the subtree of a [`LineBoil`] root again once its scene has spawned.
+fn reopen_line_boil_scene<T: LineBoilTarget>(
+    ready: On<SceneInstanceReady>,
+    mut commands: Commands,
+    parent_query: Query<&ChildOf>,
+    root_query: Query<Has<LineBoilComplete<T::Base>>, With<LineBoil>>,
+) {
+    reopen_line_boil_root::<T>(ready.entity, &mut commands, &parent_query, &root_query);
+}
+
+/// Walks the subtree of a completed [`LineBoil`] root again when an unconverted mesh shows
+/// up in it later, e.g. spawned by gameplay or reparented under it.
+#[allow(clippy::type_complexity)]
+fn reopen_line_boil_roots<T: LineBoilTarget>(
+    mut commands: Commands,
+    mesh_query: Query<
+        Entity,
+        (
+            Or<(Added<T::Source>, Changed<ChildOf>)>,
+            With<T::Source>,
+            Without<LineBoilApplied>,
+        ),
     >,
-    standard_materials: &Assets<StandardMaterial>,
-    line_boil_materials: &mut Assets<ExtendedMaterial<StandardMaterial, LineBoilMaterial>>,
+    parent_query: Query<&ChildOf>,
+    root_query: Query<Has<LineBoilComplete<T::Base>>, With<LineBoil>>,
+) {
+    for entity in mesh_query.iter() {
+        reopen_line_boil_root::<T>(entity, &mut commands, &parent_query, &root_query);
+    }
+}
+
+/// Removes [`LineBoilComplete`] from the nearest [`LineBoil`] root at or above `entity`.
+fn reopen_line_boil_root<T: LineBoilTarget>(
+    entity: Entity,
     commands: &mut Commands,
+    parent_query: &Query<&ChildOf>,
+    root_query: &Query<Has<LineBoilComplete<T::Base>>, With<LineBoil>>,
 ) {
-    if let Ok((_, mat_handle)) = mesh_query.get(entity) {
-        if let Some(std_mat) = standard_materials.get(&mat_handle.0) {
-            let extended = ExtendedMaterial {
-                base: std_mat.clone(),
-                extension: LineBoilMaterial {},
-            };
-            let new_handle = line_boil_materials.add(extended);
+    let Some((root, complete)) = std::iter::once(entity)
+        .chain(parent_query.iter_ancestors(entity))
+        .find_map(|ancestor| root_query.get(ancestor).ok().map(|complete| (ancestor, complete)))
+    else {
+        return;
+    };
+    if complete {
+        commands.entity(root).remove::<LineBoilComplete<T::Base>>();
+    }
+}
 
-            commands
-                .entity(entity)
-                .remove::<MeshMaterial3d<StandardMaterial>>()
-                .insert(MeshMaterial3d(new_handle))
-                .insert(LineBoilApplied);
+/// Puts the original materials back on the meshes of a root whose [`LineBoil`] is removed,
+/// and despawns their pass and view copies, so adding it again converts them afresh.
+#[allow(clippy::type_complexity)]
+fn restore_line_boil_materials<T: LineBoilTarget>(
+    remove: On<Remove, LineBoil>,
+    mut commands: Commands,
+    mut material_cache: ResMut<LineBoilMaterialCache<T>>,
+    mesh_query: Query<(
+        Entity,
+        &LineBoilApplied,
+        Option<&LineBoilOriginalMaterial<T>>,
+        Has<LineBoilPassCopy>,
+        Has<LineBoilViewCopy>,
+    )>,
+) {
+    let root = remove.entity;
+    // The root may be despawning along with its hierarchy, hence the `try_` commands.
+    commands.entity(root).try_remove::<(
+        LineBoilStarted,
+        LineBoilComplete<T::Base>,
+        LineBoilOwnTime,
+        LineBoilKick,
+    )>();
+    material_cache
+        .0
+        .retain(|&(cached_root, _), _| cached_root != root);
+    for (entity, applied, original, pass_copy, view_copy) in mesh_query.iter() {
+        if applied.root != root {
+            continue;
+        }
+        if pass_copy || view_copy {
+            commands.entity(entity).try_despawn();
+            continue;
+        }
+
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.try_remove::<(
+            T::Converted,
+            LineBoilApplied,
+            LineBoilOriginalMaterial<T>,
+        )>();
+        if let Some(original) = original {
+            entity_commands.try_insert(T::Source::from(original.material.clone()));
+            if original.added_tag {
+                entity_commands.try_remove::<MeshTag>();
+            }
+        }
+    }
+}
+
+/// Warns when a newly added [`LineBoil`] is nested inside (or wraps) another one.
+///
+/// Nesting authored through glTF extras is intentional and not reported.
+fn warn_nested_line_boil(
+    added_query: Query<(Entity, Has<LineBoilFromExtras>), Added<LineBoil>>,
+    line_boil_query: Query<Has<LineBoilFromExtras>, With<LineBoil>>,
+    parent_query: Query<&ChildOf>,
+    children_query: Query<&Children>,
+) {
+    for (entity, from_extras) in added_query.iter() {
+        let outer = parent_query
+            .iter_ancestors(entity)
+            .find(|&ancestor| line_boil_query.contains(ancestor))
+            .filter(|_| !from_extras);
+        // Pairs added in the same frame are reported once, from the inner side.
+        let inner = children_query.iter_descendants(entity).find(|&descendant| {
+            matches!(line_boil_query.get(descendant), Ok(false))
+                && !added_query.contains(descendant)
+        });
+
+        if let Some(outer) = outer {
+            warn!(
+                "LineBoil on {entity} is nested under LineBoil on {outer}; \
+                 its own settings apply to its subtree and displacement is not stacked"
+            );
+        }
+        if let Some(inner) = inner {
+            warn!(
+                "LineBoil on {entity} wraps LineBoil on {inner}; \
+                 the inner settings apply to its subtree and displacement is not stacked"
+            );
         }
     }
+}
 
+/// Returns whether any mesh in the hierarchy was converted. Stops converting once `budget`
+/// reaches zero, decrementing it for each converted mesh. Clears `complete` if any mesh may
+/// have been left unconverted, because the budget ran out or its material isn't loaded yet.
+///
+/// `member` is the child of `root` that `entity` belongs to (or `root` itself), and `depth`
+/// how many levels below `root` it is. Meshes reuse the cached material of their source
+/// material; for crowds, untagged meshes are also tagged with their member.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn traverse_and_replace_materials<T: LineBoilTarget>(
+    entity: Entity,
+    root: Entity,
+    member: Entity,
+    depth: usize,
+    line_boil: &LineBoil,
+    material_cache: &mut LineBoilMaterialCache<T>,
+    budget: &mut usize,
+    complete: &mut bool,
+    boundary_query: &Query<(), Or<(With<LineBoil>, With<LineBoilStop>)>>,
+    children_query: &Query<&Children>,
+    mesh_query: &Query<(Entity, &T::Source, Has<MeshTag>), Without<LineBoilApplied>>,
+    base_materials: &Assets<T::Base>,
+    line_boil_materials: &mut Assets<T::Boiled>,
+    commands: &mut Commands,
+) -> bool {
+    let mut converted = false;
+    if *budget == 0 {
+        *complete = false;
+        return converted;
+    }
+
+    let mesh = mesh_query.get(entity).ok();
+    if mesh.is_some_and(|(_, mat_handle, _)| !base_materials.contains(&**mat_handle)) {
+        *complete = false;
+    }
+    if let Some((_, mat_handle, tagged)) = mesh
+        && let Some(base) = base_materials.get(&**mat_handle)
+    {
+        let cache_key = (root, mat_handle.id());
+        let cached = material_cache.0.get(&cache_key).filter(|handle| {
+            line_boil_materials
+                .get(*handle)
+                .is_some_and(|material| material.line_boil().settings == line_boil.settings)
+        });
+        let new_handle = match cached {
+            Some(handle) => handle.clone(),
+            None => {
+                let handle = line_boil_materials
+                    .add(T::boil(base, LineBoilMaterial::new(line_boil.settings)));
+                material_cache.0.insert(cache_key, handle.clone());
+                handle
+            }
+        };
+
+        let added_tag = line_boil.settings.per_instance_seed && !tagged;
+        let mut entity_commands = commands.entity(entity);
+        entity_commands
+            .remove::<T::Source>()
+            .insert(T::Converted::from(new_handle))
+            .insert(LineBoilApplied { root })
+            .insert(LineBoilOriginalMaterial::<T> {
+                material: (**mat_handle).clone(),
+                added_tag,
+            });
+        if added_tag {
+            entity_commands.insert(MeshTag(member.index_u32()));
+        }
+        converted = true;
+        *budget -= 1;
+    }
+
+    if line_boil.max_depth.is_some_and(|max_depth| depth >= max_depth) {
+        return converted;
+    }
     if let Ok(children) = children_query.get(entity) {
         for child in children.iter() {
-            traverse_and_replace_materials(
+            // A nested `LineBoil` owns its own subtree, so meshes are never wrapped twice.
+            if boundary_query.contains(child) {
+                continue;
+            }
+            converted |= traverse_and_replace_materials(
                 child,
+                root,
+                if entity == root { child } else { member },
+                depth + 1,
+                line_boil,
+                material_cache,
+                budget,
+                complete,
+                boundary_query,
                 children_query,
                 mesh_query,
-                standard_materials,
+                base_materials,
                 line_boil_materials,
                 commands,
             );
         }
     }
+
+    converted
 }
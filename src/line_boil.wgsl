@@ -4,81 +4,15 @@
 
 #import bevy_pbr::{
     mesh_functions,
+    mesh_view_bindings::view,
     skinning,
     morph::morph,
     forward_io::{Vertex, VertexOutput},
-    view_transformations::position_world_to_clip,
 }
-#import bevy_pbr::mesh_view_bindings::globals
-
-// Hardcoded line boil settings (subtle preset)
-const INTENSITY: f32 = 0.008;
-const FRAME_RATE: f32 = 8.0;
-const NOISE_FREQUENCY: f32 = 6.0;
-const SEED: f32 = 0.0;
-
-// ============================================================================
-// Smooth value noise functions (spatially coherent - nearby vertices move together)
-// ============================================================================
-
-// Simple hash function for 3D input -> single float
-fn hash31(p: vec3<f32>) -> f32 {
-    var p3 = fract(p * 0.1031);
-    p3 += dot(p3, p3.zyx + 31.32);
-    return fract((p3.x + p3.y) * p3.z);
-}
-
-// Smooth interpolation
-fn smooth_interp(t: f32) -> f32 {
-    return t * t * (3.0 - 2.0 * t);
-}
-
-// 3D value noise with smooth trilinear interpolation
-// This ensures nearby points get similar values (no vertex clipping)
-fn value_noise_3d(p: vec3<f32>) -> f32 {
-    let i = floor(p);
-    let f = fract(p);
-
-    // Smooth interpolation weights
-    let u = vec3<f32>(smooth_interp(f.x), smooth_interp(f.y), smooth_interp(f.z));
-
-    // Hash at 8 corners of the cell
-    let n000 = hash31(i + vec3<f32>(0.0, 0.0, 0.0));
-    let n100 = hash31(i + vec3<f32>(1.0, 0.0, 0.0));
-    let n010 = hash31(i + vec3<f32>(0.0, 1.0, 0.0));
-    let n110 = hash31(i + vec3<f32>(1.0, 1.0, 0.0));
-    let n001 = hash31(i + vec3<f32>(0.0, 0.0, 1.0));
-    let n101 = hash31(i + vec3<f32>(1.0, 0.0, 1.0));
-    let n011 = hash31(i + vec3<f32>(0.0, 1.0, 1.0));
-    let n111 = hash31(i + vec3<f32>(1.0, 1.0, 1.0));
-
-    // Trilinear interpolation
-    let n00 = mix(n000, n100, u.x);
-    let n10 = mix(n010, n110, u.x);
-    let n01 = mix(n001, n101, u.x);
-    let n11 = mix(n011, n111, u.x);
-    let n0 = mix(n00, n10, u.y);
-    let n1 = mix(n01, n11, u.y);
-    return mix(n0, n1, u.z) * 2.0 - 1.0;  // Return -1 to 1
-}
-
-// Quantize time to create frame-held effect
-fn quantize_time(time: f32, fps: f32) -> f32 {
-    return floor(time * fps);
-}
-
-// Smooth 3D displacement vector - nearby vertices get similar displacement
-fn smooth_turbulent_noise(pos: vec3<f32>, time_q: f32, seed: f32) -> vec3<f32> {
-    let p = pos * NOISE_FREQUENCY + seed;
-    let t = time_q;
-
-    // Sample smooth noise for each axis with different offsets
-    // This creates a coherent wave-like displacement field
-    return vec3<f32>(
-        value_noise_3d(p + vec3<f32>(t * 1.0, 0.0, 0.0)),
-        value_noise_3d(p + vec3<f32>(0.0, t * 1.3, 100.0)),
-        value_noise_3d(p + vec3<f32>(200.0, 0.0, t * 0.7))
-    );
+#import bevy_line_boil::common::{
+    line_boil_clip_position,
+    line_boil_displaced_normal,
+    line_boil_instance_seed,
 }
 
 // ============================================================================
@@ -86,7 +20,25 @@ fn smooth_turbulent_noise(pos: vec3<f32>, time_q: f32, seed: f32) -> vec3<f32> {
 // ============================================================================
 
 @vertex
-fn vertex(vertex_no_morph: Vertex) -> VertexOutput {
+fn vertex(
+    vertex_no_morph: Vertex,
+#ifdef LINE_BOIL_AREA_RESPONSE
+    // Must match `ATTRIBUTE_BOIL_AREA_LOCATION` in area.rs
+    @location(16) boil_area: f32,
+#endif
+#ifdef LINE_BOIL_CREASE
+    // Must match `ATTRIBUTE_BOIL_CREASE_LOCATION` in crease.rs
+    @location(17) boil_crease: f32,
+#endif
+#ifdef LINE_BOIL_MASK
+    // Must match `ATTRIBUTE_BOIL_MASK_LOCATION` in mask.rs
+#ifdef LINE_BOIL_MASK_CHANNEL
+    @location(19) boil_mask: vec4<f32>,
+#else
+    @location(19) boil_mask: f32,
+#endif
+#endif
+) -> VertexOutput {
     var out: VertexOutput;
 
     // Handle morphing if enabled
@@ -120,24 +72,70 @@ fn vertex(vertex_no_morph: Vertex) -> VertexOutput {
     // LINE BOIL DISPLACEMENT (Screen-space for hand-drawn effect)
     // ========================================================================
 
-    // Transform to clip space first
-    var clip_position = position_world_to_clip(world_position.xyz);
-
-    // Quantize time to create frame-held effect (classic animation look)
-    let time_quantized = quantize_time(globals.time, FRAME_RATE);
-
-    // Use screen-space position (NDC) for noise - movement through 3D space won't affect boil
-    let screen_pos = clip_position.xy / clip_position.w;
-    let noise = smooth_turbulent_noise(vec3<f32>(screen_pos, 0.0), time_quantized, SEED);
+    var local_normal = vec3<f32>(0.0);
+#ifdef VERTEX_NORMALS
+    local_normal = vertex.normal;
+#endif
+    var boil_area_weight = 1.0;
+#ifdef LINE_BOIL_AREA_RESPONSE
+    boil_area_weight = boil_area;
+#endif
+    var boil_crease_weight = 0.0;
+#ifdef LINE_BOIL_CREASE
+    boil_crease_weight = boil_crease;
+#endif
+    var boil_mask_weight = 1.0;
+#ifdef LINE_BOIL_MASK
+#ifdef LINE_BOIL_MASK_CHANNEL
+    boil_mask_weight = boil_mask[#{LINE_BOIL_MASK_CHANNEL}];
+#else
+    boil_mask_weight = boil_mask;
+#endif
+#endif
+    var instance_seed = 0.0;
+#ifdef LINE_BOIL_INSTANCE_SEED
+    instance_seed = line_boil_instance_seed(mesh_functions::get_tag(vertex.instance_index));
+#endif
+    let clip_position = line_boil_clip_position(
+        world_position.xyz,
+        vertex.position,
+        local_normal,
+        world_from_local,
+        view.clip_from_world,
+        boil_area_weight,
+        boil_crease_weight,
+        boil_mask_weight,
+        instance_seed
+    );
 
-    // Displace in screen space (X and Y only) - like lines drawn on paper wobbling
-    // Scale by w to keep displacement consistent regardless of depth
-    clip_position.x += noise.x * INTENSITY * clip_position.w;
-    clip_position.y += noise.y * INTENSITY * clip_position.w;
+#ifdef LINE_BOIL_RECOMPUTE_NORMALS
+#ifdef VERTEX_NORMALS
+    var local_tangent = vec3<f32>(0.0);
+#ifdef VERTEX_TANGENTS
+    local_tangent = vertex.tangent.xyz;
+#endif
+    world_normal = line_boil_displaced_normal(
+        clip_position,
+        world_normal,
+        vertex.position,
+        local_normal,
+        local_tangent,
+        world_from_local,
+        view.clip_from_world,
+        view.world_from_clip,
+        boil_area_weight,
+        boil_crease_weight,
+        boil_mask_weight,
+        instance_seed
+    );
+#endif
+#endif
 
     // ========================================================================
 
     out.position = clip_position;
+    // Keep the undisplaced world position: fragment lighting and distance fog read it,
+    // so they stay stable (no fog band shimmer) while the silhouette boils
     out.world_position = world_position;
 
 #ifdef VERTEX_NORMALS
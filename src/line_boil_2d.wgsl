@@ -0,0 +1,101 @@
+// Line Boil 2D Vertex Shader
+// The line boil displacement for `Mesh2d`s, in place of Bevy's 2D mesh vertex shader.
+// Displacement stays in the 2D plane: 2D meshes always boil in screen space.
+
+#import bevy_sprite::{
+    mesh2d_functions as mesh_functions,
+    mesh2d_vertex_output::VertexOutput,
+    mesh2d_view_bindings::view,
+}
+#import bevy_line_boil::common::{
+    line_boil_clip_position,
+    line_boil_instance_seed,
+}
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+#ifdef VERTEX_POSITIONS
+    @location(0) position: vec3<f32>,
+#endif
+#ifdef VERTEX_NORMALS
+    @location(1) normal: vec3<f32>,
+#endif
+#ifdef VERTEX_UVS
+    @location(2) uv: vec2<f32>,
+#endif
+#ifdef VERTEX_TANGENTS
+    @location(3) tangent: vec4<f32>,
+#endif
+#ifdef VERTEX_COLORS
+    @location(4) color: vec4<f32>,
+#endif
+#ifdef LINE_BOIL_MASK
+    // Must match `ATTRIBUTE_BOIL_MASK_LOCATION` in mask.rs
+#ifdef LINE_BOIL_MASK_CHANNEL
+    @location(19) boil_mask: vec4<f32>,
+#else
+    @location(19) boil_mask: f32,
+#endif
+#endif
+};
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+#ifdef VERTEX_UVS
+    out.uv = vertex.uv;
+#endif
+
+#ifdef VERTEX_POSITIONS
+    var world_from_local = mesh_functions::get_world_from_local(vertex.instance_index);
+    out.world_position = mesh_functions::mesh2d_position_local_to_world(
+        world_from_local,
+        vec4<f32>(vertex.position, 1.0)
+    );
+
+    var local_normal = vec3<f32>(0.0);
+#ifdef VERTEX_NORMALS
+    local_normal = vertex.normal;
+#endif
+    var boil_mask_weight = 1.0;
+#ifdef LINE_BOIL_MASK
+#ifdef LINE_BOIL_MASK_CHANNEL
+    boil_mask_weight = vertex.boil_mask[#{LINE_BOIL_MASK_CHANNEL}];
+#else
+    boil_mask_weight = vertex.boil_mask;
+#endif
+#endif
+    var instance_seed = 0.0;
+#ifdef LINE_BOIL_INSTANCE_SEED
+    instance_seed = line_boil_instance_seed(mesh_functions::get_tag(vertex.instance_index));
+#endif
+    // No area or crease weights: 2D meshes don't get those attributes
+    out.position = line_boil_clip_position(
+        out.world_position.xyz,
+        vertex.position,
+        local_normal,
+        world_from_local,
+        view.clip_from_world,
+        1.0,
+        0.0,
+        boil_mask_weight,
+        instance_seed
+    );
+#endif
+
+#ifdef VERTEX_NORMALS
+    out.world_normal = mesh_functions::mesh2d_normal_local_to_world(vertex.normal, vertex.instance_index);
+#endif
+
+#ifdef VERTEX_TANGENTS
+    out.world_tangent = mesh_functions::mesh2d_tangent_local_to_world(
+        world_from_local,
+        vertex.tangent
+    );
+#endif
+
+#ifdef VERTEX_COLORS
+    out.color = vertex.color;
+#endif
+    return out;
+}
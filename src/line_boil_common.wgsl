@@ -0,0 +1,537 @@
+// Line Boil shared definitions
+// Effect parameters and the turbulence noise, shared by the line boil vertex and fragment shaders.
+
+#define_import_path bevy_line_boil::common
+
+// Kept free of bevy_pbr imports so the readback compute shader can use it as well
+
+// Must match `LineBoilUniform` in material.rs
+struct LineBoilSettings {
+    intensity: f32,
+    frame_rate: f32,
+    noise_frequency: f32,
+    seed: f32,
+    seed_drift: f32,
+    area_response: f32,
+    time: f32,
+    detail_normal_strength: f32,
+    // Unit object-space axis, or zero when mirroring is disabled
+    mirror_axis: vec3<f32>,
+    // World-space lean (direction * strength), or zero
+    bias: vec3<f32>,
+    crease_preservation: f32,
+    sheet_thickness: f32,
+    // Per-axis object-space frequency, or zero for the isotropic `noise_frequency`
+    noise_frequency_axes: vec3<f32>,
+    emissive_pulse: f32,
+    // World-space polyline the boil flows along (xyz), used when LINE_BOIL_PATH is set.
+    // The array length must match `LineBoilPath::MAX_POINTS`
+    path_point_count: u32,
+    path_flow_speed: f32,
+    path_alignment: f32,
+    path_points: array<vec4<f32>, 8>,
+    intensity_noise_strength: f32,
+    intensity_noise_frequency: f32,
+    falloff_start: f32,
+    falloff_end: f32,
+    frame_blend: f32,
+    // Object-space box the boil is limited to, used when LINE_BOIL_REGION is set
+    region_min: vec3<f32>,
+    region_max: vec3<f32>,
+    region_margin: f32,
+    // Seed drift accumulated up to the current held frame, from the unwrapped frame count
+    seed_drift_offset: f32,
+}
+
+@group(#{MATERIAL_BIND_GROUP}) @binding(100) var<uniform> line_boil: LineBoilSettings;
+
+// ============================================================================
+// Smooth value noise functions (spatially coherent - nearby vertices move together)
+// Mirrored on the CPU in noise.rs - keep both in sync
+// ============================================================================
+
+// Simple hash function for 3D input -> single float
+fn hash31(p: vec3<f32>) -> f32 {
+    var p3 = fract(p * 0.1031);
+    p3 += dot(p3, p3.zyx + 31.32);
+    return fract((p3.x + p3.y) * p3.z);
+}
+
+// Smooth interpolation
+fn smooth_interp(t: f32) -> f32 {
+    return t * t * (3.0 - 2.0 * t);
+}
+
+// 3D value noise with smooth trilinear interpolation
+// This ensures nearby points get similar values (no vertex clipping)
+fn value_noise_3d(p: vec3<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+
+    // Smooth interpolation weights
+    let u = vec3<f32>(smooth_interp(f.x), smooth_interp(f.y), smooth_interp(f.z));
+
+    // Hash at 8 corners of the cell
+    let n000 = hash31(i + vec3<f32>(0.0, 0.0, 0.0));
+    let n100 = hash31(i + vec3<f32>(1.0, 0.0, 0.0));
+    let n010 = hash31(i + vec3<f32>(0.0, 1.0, 0.0));
+    let n110 = hash31(i + vec3<f32>(1.0, 1.0, 0.0));
+    let n001 = hash31(i + vec3<f32>(0.0, 0.0, 1.0));
+    let n101 = hash31(i + vec3<f32>(1.0, 0.0, 1.0));
+    let n011 = hash31(i + vec3<f32>(0.0, 1.0, 1.0));
+    let n111 = hash31(i + vec3<f32>(1.0, 1.0, 1.0));
+
+    // Trilinear interpolation
+    let n00 = mix(n000, n100, u.x);
+    let n10 = mix(n010, n110, u.x);
+    let n01 = mix(n001, n101, u.x);
+    let n11 = mix(n011, n111, u.x);
+    let n0 = mix(n00, n10, u.y);
+    let n1 = mix(n01, n11, u.y);
+    return mix(n0, n1, u.z) * 2.0 - 1.0;  // Return -1 to 1
+}
+
+// Held frames after which the pattern repeats. Every held frame is a fresh sample anyway,
+// so wrapping is seamless and keeps noise inputs small as the app runs for hours.
+// Set from noise.rs, like the axis constants of `turbulent_field`
+const TIME_WRAP_FRAMES: f32 = f32(#{LINE_BOIL_TIME_WRAP_FRAMES}u);
+
+// Noise sample coordinates are clamped to this NDC range. Vertices of huge meshes (backdrops,
+// skydomes) can land far off screen or near the camera plane, where xy / w explodes and
+// the hash loses precision
+const SAMPLE_LIMIT: f32 = 16.0;
+
+// Quantize time to create frame-held effect
+fn quantize_time(time: f32, fps: f32) -> f32 {
+    return floor(time * fps) % TIME_WRAP_FRAMES;
+}
+
+// How far the held frame has eased into the next one: 0 until the last `blend` of the frame,
+// then smoothly up to exactly 1 as the next frame begins
+fn frame_blend_weight(time: f32, fps: f32, blend: f32) -> f32 {
+    let ease = clamp(blend, 1e-4, 1.0);
+    return smooth_interp(clamp((fract(time * fps) - (1.0 - ease)) / ease, 0.0, 1.0));
+}
+
+// Seed advanced by its drift, stepping only when a new frame is held. The drift up to the
+// current frame comes from the CPU, as the wrapped `time_q` would snap it back
+fn drifted_seed(seed: f32, drift_offset: f32, drift: f32, frames_ahead: f32, fps: f32) -> f32 {
+    return seed + drift_offset + drift * frames_ahead / max(fps, 0.0001);
+}
+
+// Dot product of `offset` with one of 12 cube edge directions picked by `hash`, as in
+// improved Perlin noise
+fn simplex_gradient_dot(hash: f32, offset: vec3<f32>) -> f32 {
+    let h = min(u32(hash * 16.0), 15u);
+    let u = select(offset.y, offset.x, h < 8u);
+    let v = select(select(offset.z, offset.x, h == 12u || h == 14u), offset.y, h < 4u);
+    return select(-u, u, (h & 1u) == 0u) + select(-v, v, (h & 2u) == 0u);
+}
+
+// 3D simplex noise. No axis-aligned grid to read as "digital", at a cost similar to
+// `value_noise_3d` (four hashed corners instead of eight)
+fn simplex_noise_3d(p: vec3<f32>) -> f32 {
+    // Skew into the simplex grid, find the cell and unskew its origin
+    let i = floor(p + (p.x + p.y + p.z) / 3.0);
+    let x0 = p - (i - (i.x + i.y + i.z) / 6.0);
+
+    // Corners of the tetrahedron containing the point, ordered by the largest offset
+    let g = step(x0.yzx, x0.xyz);
+    let l = 1.0 - g;
+    let i1 = min(g, l.zxy);
+    let i2 = max(g, l.zxy);
+    let x1 = x0 - i1 + 1.0 / 6.0;
+    let x2 = x0 - i2 + 1.0 / 3.0;
+    let x3 = x0 - 0.5;
+
+    var sum = 0.0;
+    var corners = array<vec3<f32>, 4>(vec3<f32>(0.0), i1, i2, vec3<f32>(1.0));
+    var offsets = array<vec3<f32>, 4>(x0, x1, x2, x3);
+    for (var c = 0u; c < 4u; c += 1u) {
+        let w = max(0.6 - dot(offsets[c], offsets[c]), 0.0);
+        let w2 = w * w;
+        sum += w2 * w2 * simplex_gradient_dot(hash31(i + corners[c]), offsets[c]);
+    }
+    return clamp(32.0 * sum, -1.0, 1.0);
+}
+
+// Noise of the selected `NoiseKind` used for the displacement field
+fn boil_noise_3d(p: vec3<f32>) -> f32 {
+#ifdef LINE_BOIL_NOISE_SIMPLEX
+    return simplex_noise_3d(p);
+#else
+    return value_noise_3d(p);
+#endif
+}
+
+// Smooth 3D displacement vector - nearby vertices get similar displacement
+fn smooth_turbulent_noise(pos: vec3<f32>, time_q: f32, frequency: f32, seed: f32) -> vec3<f32> {
+    let p = pos * frequency + seed;
+#ifdef LINE_BOIL_NOISE_CURL
+    return curl_noise(p, time_q);
+#else
+    return turbulent_field(p, time_q);
+#endif
+}
+
+// Sample smooth noise for each axis with different offsets at noise coordinate `p`.
+// This creates a coherent wave-like displacement field
+fn turbulent_field(p: vec3<f32>, t_q: f32) -> vec3<f32> {
+    // Per-axis time scales and noise field offsets decorrelating the axes, set from noise.rs
+    // as the bits of its floats
+    let t = t_q * bitcast<vec3<f32>>(vec3<u32>(
+        #{LINE_BOIL_AXIS_TIME_SCALE_X}u,
+        #{LINE_BOIL_AXIS_TIME_SCALE_Y}u,
+        #{LINE_BOIL_AXIS_TIME_SCALE_Z}u
+    ));
+    let offset_0 = bitcast<vec3<f32>>(vec3<u32>(
+        #{LINE_BOIL_AXIS_OFFSET_0_X}u,
+        #{LINE_BOIL_AXIS_OFFSET_0_Y}u,
+        #{LINE_BOIL_AXIS_OFFSET_0_Z}u
+    ));
+    let offset_1 = bitcast<vec3<f32>>(vec3<u32>(
+        #{LINE_BOIL_AXIS_OFFSET_1_X}u,
+        #{LINE_BOIL_AXIS_OFFSET_1_Y}u,
+        #{LINE_BOIL_AXIS_OFFSET_1_Z}u
+    ));
+    let offset_2 = bitcast<vec3<f32>>(vec3<u32>(
+        #{LINE_BOIL_AXIS_OFFSET_2_X}u,
+        #{LINE_BOIL_AXIS_OFFSET_2_Y}u,
+        #{LINE_BOIL_AXIS_OFFSET_2_Z}u
+    ));
+    return vec3<f32>(
+        boil_noise_3d(p + offset_0 + vec3<f32>(t.x, 0.0, 0.0)),
+        boil_noise_3d(p + offset_1 + vec3<f32>(0.0, t.y, 0.0)),
+        boil_noise_3d(p + offset_2 + vec3<f32>(0.0, 0.0, t.z))
+    );
+}
+
+// Step in noise coordinates of the finite differences taken by `curl_noise`
+const CURL_STEP: f32 = 0.01;
+
+// Brings the typical size of `curl_noise` in line with the other noise kinds
+const CURL_SCALE: f32 = 0.5;
+
+// Curl of `turbulent_field` used as a vector potential: a divergence-free, swirling field
+// like ink bleeding into paper. Four times the cost of `turbulent_field`
+fn curl_noise(p: vec3<f32>, t: f32) -> vec3<f32> {
+    let f = turbulent_field(p, t);
+    let dx = (turbulent_field(p + vec3<f32>(CURL_STEP, 0.0, 0.0), t) - f) / CURL_STEP;
+    let dy = (turbulent_field(p + vec3<f32>(0.0, CURL_STEP, 0.0), t) - f) / CURL_STEP;
+    let dz = (turbulent_field(p + vec3<f32>(0.0, 0.0, CURL_STEP), t) - f) / CURL_STEP;
+    let curl = vec3<f32>(dy.z - dz.y, dz.x - dx.z, dx.y - dy.x);
+    return clamp(curl * CURL_SCALE, vec3<f32>(-1.0), vec3<f32>(1.0));
+}
+
+// ============================================================================
+// Displacement (shared by the main and prepass vertex shaders so they can't drift apart)
+// ============================================================================
+
+// Noise sample coordinate (clamped NDC) of a clip-space position
+fn sample_position(clip_position: vec4<f32>) -> vec2<f32> {
+    return clamp(
+        clip_position.xy / max(abs(clip_position.w), 1e-6),
+        vec2<f32>(-SAMPLE_LIMIT),
+        vec2<f32>(SAMPLE_LIMIT)
+    );
+}
+
+// Noise sample point of a world position: its clamped NDC (with zero z), or with
+// LINE_BOIL_WORLD_SPACE the world position itself
+fn boil_sample_point(world_position: vec3<f32>, clip_from_world: mat4x4<f32>) -> vec3<f32> {
+#ifdef LINE_BOIL_WORLD_SPACE
+    return world_position;
+#else
+    return vec3<f32>(sample_position(clip_from_world * vec4<f32>(world_position, 1.0)), 0.0);
+#endif
+}
+
+// Unit direction of the `line_boil.path_points` segment nearest to `world_position`
+fn nearest_path_tangent(world_position: vec3<f32>) -> vec3<f32> {
+    var tangent = vec3<f32>(0.0);
+    var nearest = 3.4e38;
+    for (var i = 1u; i < line_boil.path_point_count; i += 1u) {
+        let a = line_boil.path_points[i - 1u].xyz;
+        let ab = line_boil.path_points[i].xyz - a;
+        let t = clamp(dot(world_position - a, ab) / max(dot(ab, ab), 1e-12), 0.0, 1.0);
+        let d = distance(world_position, a + ab * t);
+        if d < nearest {
+            nearest = d;
+            tangent = normalize(ab);
+        }
+    }
+    return tangent;
+}
+
+// Seed offsets of crowd members wrap after this many `MeshTag` values, keeping noise inputs small
+const INSTANCE_SEED_WRAP: u32 = 4096u;
+
+// Drift of the intensity modulation field through time, in cycles per second
+const INTENSITY_NOISE_SPEED: f32 = 0.5;
+
+// Seed offset of a crowd member from its mesh tag
+fn line_boil_instance_seed(tag: u32) -> f32 {
+    return f32(tag % INSTANCE_SEED_WRAP);
+}
+
+// Clip position of a vertex with the line boil applied, as seen through `clip_from_world`.
+// `local_normal` may be zero when the mesh has no normals. `boil_area` and `boil_crease` are the vertex's `ATTRIBUTE_BOIL_AREA`
+// and `ATTRIBUTE_BOIL_CREASE` values, only read with LINE_BOIL_AREA_RESPONSE and
+// LINE_BOIL_CREASE respectively, `boil_mask` its `mask_attribute` weight (1 without a
+// mask), and `instance_seed` the `line_boil_instance_seed` of the mesh tag, only read with
+// LINE_BOIL_INSTANCE_SEED
+fn line_boil_clip_position(
+    world_position: vec3<f32>,
+    local_position: vec3<f32>,
+    local_normal: vec3<f32>,
+    world_from_local: mat4x4<f32>,
+    clip_from_world: mat4x4<f32>,
+    boil_area: f32,
+    boil_crease: f32,
+    boil_mask: f32,
+    instance_seed: f32,
+) -> vec4<f32> {
+    // Transform to clip space first, pushed along the per-entity lean
+    var clip_position = clip_from_world * vec4<f32>(world_position + line_boil.bias, 1.0);
+
+    // Quantize time to create frame-held effect (classic animation look)
+    let time_quantized = quantize_time(line_boil.time, line_boil.frame_rate);
+
+    // Use screen-space position (NDC) for noise - movement through 3D space won't affect boil.
+    // In world space the field is fixed to the world instead
+    var sample_pos = boil_sample_point(world_position + line_boil.bias, clip_from_world);
+    var sample_local = local_position;
+
+#ifdef LINE_BOIL_THIN_SHEET
+    // Sample on the sheet's midsurface: both layers of a double-sided sheet sit half the
+    // thickness along their own (opposite) normals from it, so they get the same offset
+    // and can't displace into each other
+    sample_local -= local_normal * (0.5 * line_boil.sheet_thickness);
+    let sheet_world = world_from_local * vec4<f32>(sample_local, 1.0);
+    sample_pos = boil_sample_point(sheet_world.xyz + line_boil.bias, clip_from_world);
+#endif
+
+    var frequency = line_boil.noise_frequency;
+
+#ifdef LINE_BOIL_NOISE_AXES
+    // Squash the sample point along the object's axes by their frequency relative to the
+    // highest one, which becomes the noise frequency. Screen-space noise then varies fastest
+    // along the highest-frequency axis, keeping the boil aligned with e.g. wood grain
+    let axes = max(line_boil.noise_frequency_axes, vec3<f32>(0.0));
+    frequency = max(axes.x, max(axes.y, axes.z));
+    sample_local *= axes / max(frequency, 1e-6);
+    let stretched_world = world_from_local * vec4<f32>(sample_local, 1.0);
+    sample_pos = boil_sample_point(stretched_world.xyz + line_boil.bias, clip_from_world);
+#endif
+
+#ifdef LINE_BOIL_MIRROR
+    // Fold the sample point onto the positive side of the mirror plane so both halves
+    // of a symmetric model sample the same noise
+    let mirror_axis = line_boil.mirror_axis;
+    let mirror_side = dot(sample_local, mirror_axis);
+    let folded_local = sample_local - 2.0 * min(mirror_side, 0.0) * mirror_axis;
+    let folded_world = world_from_local * vec4<f32>(folded_local, 1.0);
+    // Kept apart from `sample_pos`, which the path flow shifts, to build the reflect axis
+    let mirror_origin = boil_sample_point(folded_world.xyz + line_boil.bias, clip_from_world);
+    sample_pos = mirror_origin;
+#endif
+
+#ifdef LINE_BOIL_CLIP_SHAKE
+    // One sample for the whole object: it shakes as a flat unit, like a drawing on a
+    // vibrating camera stand, re-rolled on each held frame
+    sample_pos = vec3<f32>(0.0);
+#endif
+
+#ifdef LINE_BOIL_PATH
+    // Screen direction of the nearest path segment. The pattern travels along it on held
+    // frames; added after clamping as it only shifts which part of the noise field is read
+    let path_tangent = nearest_path_tangent(world_position);
+#ifdef LINE_BOIL_WORLD_SPACE
+    let path_direction = path_tangent;
+#else
+    let path_ahead = clip_from_world * vec4<f32>(world_position + line_boil.bias + path_tangent * 0.01, 1.0);
+    let path_screen = path_ahead.xy / max(abs(path_ahead.w), 1e-6)
+        - clip_position.xy / max(abs(clip_position.w), 1e-6);
+    let path_direction = vec3<f32>(path_screen / max(length(path_screen), 1e-6), 0.0);
+#endif
+    let held_time = time_quantized / max(line_boil.frame_rate, 0.0001);
+    sample_pos -= path_direction * line_boil.path_flow_speed * held_time;
+#endif
+
+    var seed = line_boil.seed;
+#ifdef LINE_BOIL_INSTANCE_SEED
+    // Crowd members share one material, so their seeds come from the per-instance mesh tag
+    seed += instance_seed;
+#endif
+
+    var noise = smooth_turbulent_noise(
+        sample_pos,
+        time_quantized,
+        frequency,
+        drifted_seed(seed, line_boil.seed_drift_offset, line_boil.seed_drift, 0.0, line_boil.frame_rate)
+    );
+
+#ifdef LINE_BOIL_FRAME_BLEND
+    // Ease into the next held frame's sample instead of snapping to it
+    let time_next = (time_quantized + 1.0) % TIME_WRAP_FRAMES;
+    let next_noise = smooth_turbulent_noise(
+        sample_pos,
+        time_next,
+        frequency,
+        drifted_seed(seed, line_boil.seed_drift_offset, line_boil.seed_drift, 1.0, line_boil.frame_rate)
+    );
+    let blend = frame_blend_weight(line_boil.time, line_boil.frame_rate, line_boil.frame_blend);
+    noise = mix(noise, next_noise, blend);
+#endif
+
+    var intensity = line_boil.intensity;
+#ifdef LINE_BOIL_AREA_RESPONSE
+    // Scale by local triangle size (relative to the mesh average) for even visual frequency
+    intensity *= pow(max(boil_area, 0.0), line_boil.area_response);
+#endif
+#ifdef LINE_BOIL_CREASE
+    // Hold hard edges still so mechanical silhouettes stay crisp
+    intensity *= 1.0 - line_boil.crease_preservation * clamp(boil_crease, 0.0, 1.0);
+#endif
+    // Painted anchoring, e.g. planted feet
+    intensity *= max(boil_mask, 0.0);
+
+#ifdef LINE_BOIL_REGION
+    // Fade out across the boundary of the object-space box by its signed distance, so
+    // vertices on the boundary get half the displacement rather than a hard cutoff
+    let region_half = 0.5 * (line_boil.region_max - line_boil.region_min);
+    let region_q = abs(local_position - 0.5 * (line_boil.region_min + line_boil.region_max)) - region_half;
+    let region_distance = length(max(region_q, vec3<f32>(0.0)))
+        + min(max(region_q.x, max(region_q.y, region_q.z)), 0.0);
+    let region_edge = max(0.5 * line_boil.region_margin, 1e-4);
+    intensity *= 1.0 - smoothstep(-region_edge, region_edge, region_distance);
+#endif
+
+#ifdef LINE_BOIL_FALLOFF
+    // Fade out with view depth (w of a perspective clip position), so distant objects don't
+    // shimmer sub-pixel. Smooth so objects moving through the range don't pop. Orthographic
+    // views have a constant w (no view depth in the w row of clip_from_world), so skip them
+    let clip_w_row = vec3<f32>(clip_from_world[0][3], clip_from_world[1][3], clip_from_world[2][3]);
+    if any(clip_w_row != vec3<f32>(0.0)) {
+        let falloff_end = max(line_boil.falloff_end, line_boil.falloff_start + 1e-4);
+        intensity *= 1.0 - smoothstep(line_boil.falloff_start, falloff_end, clip_position.w);
+    }
+#endif
+
+#ifdef LINE_BOIL_INTENSITY_NOISE
+    // Second-order boil: a low-frequency field drifting through time makes regions briefly
+    // boil harder, then calm down. Stepped with the held frames like the boil itself
+    let held_seconds = time_quantized / max(line_boil.frame_rate, 0.0001);
+    let energy = value_noise_3d(vec3<f32>(
+        sample_pos.xy * line_boil.intensity_noise_frequency + seed + 400.0,
+        sample_pos.z * line_boil.intensity_noise_frequency + held_seconds * INTENSITY_NOISE_SPEED
+    ));
+    intensity *= max(1.0 + line_boil.intensity_noise_strength * energy, 0.0);
+#endif
+
+#ifdef LINE_BOIL_WORLD_SPACE
+    var offset = noise;
+#else
+    var offset = vec3<f32>(noise.xy, 0.0);
+#endif
+
+#ifdef LINE_BOIL_MIRROR
+    // On the negative side, reflect the offset across the mirror plane (as seen on screen,
+    // or in world space)
+    if mirror_side < 0.0 {
+#ifdef LINE_BOIL_WORLD_SPACE
+        let axis = (world_from_local * vec4<f32>(mirror_axis, 0.0)).xyz;
+#else
+        let axis_world = world_from_local * vec4<f32>(folded_local + mirror_axis * 0.01, 1.0);
+        let axis_clip = clip_from_world * vec4<f32>(axis_world.xyz + line_boil.bias, 1.0);
+        let axis = vec3<f32>(axis_clip.xy / axis_clip.w - mirror_origin.xy, 0.0);
+#endif
+        let axis_length = length(axis);
+        if axis_length > 1e-6 {
+            let n = axis / axis_length;
+            offset -= 2.0 * dot(offset, n) * n;
+        }
+    }
+#endif
+
+#ifdef LINE_BOIL_PATH
+    // Turn the offset along the path, keeping its size and swinging back and forth
+    let along_path = path_direction * (length(offset) * noise.z);
+    offset = mix(offset, along_path, line_boil.path_alignment);
+#endif
+
+#ifdef LINE_BOIL_WORLD_SPACE
+    // Displace in world units, the same amount however the object is scaled or viewed
+    clip_position = clip_from_world * vec4<f32>(world_position + line_boil.bias + offset * intensity, 1.0);
+#else
+    // Displace in screen space (X and Y only) - like lines drawn on paper wobbling
+    // Scale by w to keep displacement consistent regardless of depth
+    clip_position.x += offset.x * intensity * clip_position.w;
+    clip_position.y += offset.y * intensity * clip_position.w;
+#endif
+
+    return clip_position;
+}
+
+// Distance in object units to the neighbours sampled by `line_boil_displaced_normal`
+const NORMAL_SAMPLE_OFFSET: f32 = 0.01;
+
+// World-space normal of the displaced surface at a vertex whose displaced clip position is
+// `clip_position`. Two neighbours a short step along the surface are displaced the same way
+// and the normal rebuilt from the three points. `local_tangent` may be zero, in which case
+// an arbitrary direction along the surface is used instead. Falls back to `world_normal`
+// (the undisplaced normal) where the rebuilt one degenerates
+fn line_boil_displaced_normal(
+    clip_position: vec4<f32>,
+    world_normal: vec3<f32>,
+    local_position: vec3<f32>,
+    local_normal: vec3<f32>,
+    local_tangent: vec3<f32>,
+    world_from_local: mat4x4<f32>,
+    clip_from_world: mat4x4<f32>,
+    world_from_clip: mat4x4<f32>,
+    boil_area: f32,
+    boil_crease: f32,
+    boil_mask: f32,
+    instance_seed: f32,
+) -> vec3<f32> {
+    let n = normalize(local_normal);
+    var tangent = local_tangent - n * dot(local_tangent, n);
+    if dot(tangent, tangent) < 1e-8 {
+        // No usable tangent: pick the axis least aligned with the normal
+        let axis = select(vec3<f32>(1.0, 0.0, 0.0), vec3<f32>(0.0, 1.0, 0.0), abs(n.x) > 0.9);
+        tangent = cross(n, axis);
+    }
+    tangent = normalize(tangent);
+    let bitangent = cross(n, tangent);
+
+    var neighbours: array<vec3<f32>, 2>;
+    var steps = array<vec3<f32>, 2>(tangent, bitangent);
+    for (var i = 0u; i < 2u; i += 1u) {
+        let neighbour_local = local_position + steps[i] * NORMAL_SAMPLE_OFFSET;
+        let neighbour_world = world_from_local * vec4<f32>(neighbour_local, 1.0);
+        let neighbour_clip = line_boil_clip_position(
+            neighbour_world.xyz,
+            neighbour_local,
+            local_normal,
+            world_from_local,
+            clip_from_world,
+            boil_area,
+            boil_crease,
+            boil_mask,
+            instance_seed
+        );
+        let displaced = world_from_clip * neighbour_clip;
+        neighbours[i] = displaced.xyz / displaced.w;
+    }
+    let center = world_from_clip * clip_position;
+    let origin = center.xyz / center.w;
+
+    let normal = cross(neighbours[0] - origin, neighbours[1] - origin);
+    let normal_length = length(normal);
+    if normal_length < 1e-12 {
+        return world_normal;
+    }
+    // Keep the side the mesh's normal faces, whatever the handedness of the transform
+    return normal / normal_length * select(1.0, -1.0, dot(normal, world_normal) < 0.0);
+}
@@ -0,0 +1,94 @@
+// Line Boil Fragment Shader
+// Standard PBR shading with a fine, frame-held shading normal perturbation layered on top of
+// the geometric boil, like pencil grain catching the light, and a frame-held flicker of the
+// emitted light. Only used when `detail_normal_strength` or `emissive_pulse` is non-zero, so
+// shading is otherwise untouched.
+// Follows bevy_pbr's `pbr.wgsl` forward path; keep in step when upgrading Bevy.
+
+#import bevy_pbr::{
+    pbr_types,
+    pbr_fragment::pbr_input_from_standard_material,
+    pbr_functions::{alpha_discard, apply_pbr_lighting, main_pass_post_lighting_processing},
+    pbr_types::STANDARD_MATERIAL_FLAGS_UNLIT_BIT,
+    forward_io::{VertexOutput, FragmentOutput},
+    decal::clustered::apply_decals,
+}
+#import bevy_line_boil::common::{line_boil, quantize_time, smooth_turbulent_noise}
+
+#ifdef VISIBILITY_RANGE_DITHER
+#import bevy_pbr::pbr_functions::visibility_range_dither;
+#endif
+
+#ifdef OIT_ENABLED
+#import bevy_core_pipeline::oit::oit_draw
+#endif
+
+// Spatial frequency of the grain, in cycles per world unit
+const DETAIL_NORMAL_FREQUENCY: f32 = 64.0;
+
+// Spatial frequency of the emissive flicker patches, in cycles per world unit
+const EMISSIVE_PULSE_FREQUENCY: f32 = 3.0;
+
+@fragment
+fn fragment(
+    in: VertexOutput,
+    @builtin(front_facing) is_front: bool,
+) -> FragmentOutput {
+#ifdef VISIBILITY_RANGE_DITHER
+    visibility_range_dither(in.position, in.visibility_range_dither);
+#endif
+
+    var pbr_input = pbr_input_from_standard_material(in, is_front);
+    pbr_input.material.base_color = alpha_discard(pbr_input.material, pbr_input.material.base_color);
+    apply_decals(&pbr_input);
+
+    let time_quantized = quantize_time(line_boil.time, line_boil.frame_rate);
+
+#ifdef LINE_BOIL_DETAIL_NORMAL
+    // Sample on the (undisplaced) surface so the grain sticks to it, re-rolled each held frame
+    let grain = smooth_turbulent_noise(
+        in.world_position.xyz,
+        time_quantized,
+        DETAIL_NORMAL_FREQUENCY,
+        line_boil.seed
+    );
+
+    // Tilt the normal within its tangent plane only
+    let n = pbr_input.N;
+    let tangent_grain = grain - n * dot(grain, n);
+    pbr_input.N = normalize(n + tangent_grain * line_boil.detail_normal_strength);
+#endif
+
+#ifdef LINE_BOIL_EMISSIVE_PULSE
+    // Brighten and dim the glow in patches, re-rolled with each held frame
+    let pulse = smooth_turbulent_noise(
+        in.world_position.xyz,
+        time_quantized,
+        EMISSIVE_PULSE_FREQUENCY,
+        line_boil.seed + 300.0
+    ).x;
+    let emissive = pbr_input.material.emissive;
+    pbr_input.material.emissive = vec4<f32>(
+        emissive.rgb * max(1.0 + pulse * line_boil.emissive_pulse, 0.0),
+        emissive.a
+    );
+#endif
+
+    var out: FragmentOutput;
+    if (pbr_input.material.flags & STANDARD_MATERIAL_FLAGS_UNLIT_BIT) == 0u {
+        out.color = apply_pbr_lighting(pbr_input);
+    } else {
+        out.color = pbr_input.material.base_color;
+    }
+    out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+
+#ifdef OIT_ENABLED
+    let alpha_mode = pbr_input.material.flags & pbr_types::STANDARD_MATERIAL_FLAGS_ALPHA_MODE_RESERVED_BITS;
+    if alpha_mode != pbr_types::STANDARD_MATERIAL_FLAGS_ALPHA_MODE_OPAQUE {
+        oit_draw(in.position, out.color);
+        discard;
+    }
+#endif
+
+    return out;
+}
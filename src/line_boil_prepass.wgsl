@@ -0,0 +1,227 @@
+// Line Boil Prepass Vertex Shader
+// Bevy's prepass vertex shader (depth, normal, motion vector and shadow passes), displaced with
+// the same `line_boil_clip_position` as the main pass when LINE_BOIL_PREPASS is set.
+// Follows bevy_pbr's `prepass.wgsl`; keep in step when upgrading Bevy.
+
+#import bevy_pbr::{
+    mesh_bindings::mesh,
+    mesh_functions,
+    mesh_view_bindings::view,
+    prepass_io::{Vertex, VertexOutput},
+    skinning,
+    morph,
+    view_transformations::position_world_to_clip,
+}
+#import bevy_line_boil::common::{line_boil_clip_position, line_boil_instance_seed}
+
+#ifdef MORPH_TARGETS
+fn morph_vertex(vertex_in: Vertex) -> Vertex {
+    var vertex = vertex_in;
+    let first_vertex = mesh[vertex.instance_index].first_vertex_index;
+    let vertex_index = vertex.index - first_vertex;
+
+    let weight_count = morph::layer_count();
+    for (var i: u32 = 0u; i < weight_count; i ++) {
+        let weight = morph::weight_at(i);
+        if weight == 0.0 {
+            continue;
+        }
+        vertex.position += weight * morph::morph(vertex_index, morph::position_offset, i);
+#ifdef VERTEX_NORMALS
+        vertex.normal += weight * morph::morph(vertex_index, morph::normal_offset, i);
+#endif
+#ifdef VERTEX_TANGENTS
+        vertex.tangent += vec4(weight * morph::morph(vertex_index, morph::tangent_offset, i), 0.0);
+#endif
+    }
+    return vertex;
+}
+
+// Previous-frame morphed position, for motion vectors only
+fn morph_prev_vertex(vertex_in: Vertex) -> Vertex {
+    var vertex = vertex_in;
+    let weight_count = morph::layer_count();
+    for (var i: u32 = 0u; i < weight_count; i ++) {
+        let weight = morph::prev_weight_at(i);
+        if weight == 0.0 {
+            continue;
+        }
+        vertex.position += weight * morph::morph(vertex.index, morph::position_offset, i);
+    }
+    return vertex;
+}
+#endif  // MORPH_TARGETS
+
+@vertex
+fn vertex(
+    vertex_no_morph: Vertex,
+#ifdef LINE_BOIL_AREA_RESPONSE
+    // Must match `ATTRIBUTE_BOIL_AREA_LOCATION` in area.rs
+    @location(16) boil_area: f32,
+#endif
+#ifdef LINE_BOIL_CREASE
+    // Must match `ATTRIBUTE_BOIL_CREASE_LOCATION` in crease.rs
+    @location(17) boil_crease: f32,
+#endif
+#ifdef LINE_BOIL_MASK
+    // Must match `ATTRIBUTE_BOIL_MASK_LOCATION` in mask.rs
+#ifdef LINE_BOIL_MASK_CHANNEL
+    @location(19) boil_mask: vec4<f32>,
+#else
+    @location(19) boil_mask: f32,
+#endif
+#endif
+#ifdef LINE_BOIL_PREPASS_NORMAL
+    // Mesh normal, added by `LineBoilMaterial::specialize` when the prepass doesn't read it
+    @location(18) boil_normal: vec3<f32>,
+#endif
+) -> VertexOutput {
+    var out: VertexOutput;
+
+#ifdef MORPH_TARGETS
+    var vertex = morph_vertex(vertex_no_morph);
+#else
+    var vertex = vertex_no_morph;
+#endif
+
+    let mesh_world_from_local = mesh_functions::get_world_from_local(vertex_no_morph.instance_index);
+
+#ifdef SKINNED
+    var world_from_local = skinning::skin_model(
+        vertex.joint_indices,
+        vertex.joint_weights,
+        vertex_no_morph.instance_index
+    );
+#else
+    // vertex_no_morph.instance_index works around a wgpu dx12 bug (gfx-rs/naga#2416)
+    var world_from_local = mesh_world_from_local;
+#endif
+
+    // Undisplaced, like the main pass, so prepass fragment outputs match it
+    out.world_position = mesh_functions::mesh_position_local_to_world(world_from_local, vec4<f32>(vertex.position, 1.0));
+
+#ifdef LINE_BOIL_PREPASS
+    var local_normal = vec3<f32>(0.0);
+#ifdef NORMAL_PREPASS_OR_DEFERRED_PREPASS
+#ifdef VERTEX_NORMALS
+    local_normal = vertex.normal;
+#endif
+#endif
+#ifdef LINE_BOIL_PREPASS_NORMAL
+    local_normal = boil_normal;
+#endif
+    var boil_area_weight = 1.0;
+#ifdef LINE_BOIL_AREA_RESPONSE
+    boil_area_weight = boil_area;
+#endif
+    var boil_crease_weight = 0.0;
+#ifdef LINE_BOIL_CREASE
+    boil_crease_weight = boil_crease;
+#endif
+    var boil_mask_weight = 1.0;
+#ifdef LINE_BOIL_MASK
+#ifdef LINE_BOIL_MASK_CHANNEL
+    boil_mask_weight = boil_mask[#{LINE_BOIL_MASK_CHANNEL}];
+#else
+    boil_mask_weight = boil_mask;
+#endif
+#endif
+    var instance_seed = 0.0;
+#ifdef LINE_BOIL_INSTANCE_SEED
+    instance_seed = line_boil_instance_seed(mesh_functions::get_tag(vertex_no_morph.instance_index));
+#endif
+    out.position = line_boil_clip_position(
+        out.world_position.xyz,
+        vertex.position,
+        local_normal,
+        world_from_local,
+        view.clip_from_world,
+        boil_area_weight,
+        boil_crease_weight,
+        boil_mask_weight,
+        instance_seed
+    );
+#else
+    out.position = position_world_to_clip(out.world_position.xyz);
+#endif
+
+#ifdef UNCLIPPED_DEPTH_ORTHO_EMULATION
+    out.unclipped_depth = out.position.z;
+    out.position.z = min(out.position.z, 1.0);
+#endif
+
+#ifdef VERTEX_UVS_A
+    out.uv = vertex.uv;
+#endif
+
+#ifdef VERTEX_UVS_B
+    out.uv_b = vertex.uv_b;
+#endif
+
+#ifdef NORMAL_PREPASS_OR_DEFERRED_PREPASS
+#ifdef VERTEX_NORMALS
+#ifdef SKINNED
+    out.world_normal = skinning::skin_normals(world_from_local, vertex.normal);
+#else
+    out.world_normal = mesh_functions::mesh_normal_local_to_world(
+        vertex.normal,
+        vertex_no_morph.instance_index
+    );
+#endif
+#endif
+
+#ifdef VERTEX_TANGENTS
+    out.world_tangent = mesh_functions::mesh_tangent_local_to_world(
+        world_from_local,
+        vertex.tangent,
+        vertex_no_morph.instance_index
+    );
+#endif
+#endif  // NORMAL_PREPASS_OR_DEFERRED_PREPASS
+
+#ifdef VERTEX_COLORS
+    out.color = vertex.color;
+#endif
+
+#ifdef MOTION_VECTOR_PREPASS
+#ifdef MORPH_TARGETS
+#ifdef HAS_PREVIOUS_MORPH
+    let prev_vertex = morph_prev_vertex(vertex_no_morph);
+#else
+    let prev_vertex = vertex_no_morph;
+#endif
+#else
+    let prev_vertex = vertex_no_morph;
+#endif
+
+#ifdef SKINNED
+#ifdef HAS_PREVIOUS_SKIN
+    let prev_model = skinning::skin_prev_model(
+        prev_vertex.joint_indices,
+        prev_vertex.joint_weights,
+        vertex_no_morph.instance_index
+    );
+#else
+    let prev_model = mesh_functions::get_previous_world_from_local(prev_vertex.instance_index);
+#endif
+#else
+    let prev_model = mesh_functions::get_previous_world_from_local(prev_vertex.instance_index);
+#endif
+
+    out.previous_world_position = mesh_functions::mesh_position_local_to_world(
+        prev_model,
+        vec4<f32>(prev_vertex.position, 1.0)
+    );
+#endif  // MOTION_VECTOR_PREPASS
+
+#ifdef VERTEX_OUTPUT_INSTANCE_INDEX
+    out.instance_index = vertex_no_morph.instance_index;
+#endif
+
+#ifdef VISIBILITY_RANGE_DITHER
+    out.visibility_range_dither = mesh_functions::get_visibility_range_dither_level(
+        vertex_no_morph.instance_index, mesh_world_from_local[3]);
+#endif
+
+    return out;
+}
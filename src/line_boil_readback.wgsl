@@ -0,0 +1,56 @@
+// Line Boil Readback Compute Shader
+// Runs the vertex displacement of `line_boil_common.wgsl` over a mesh's vertices and writes the
+// displaced world-space positions to a buffer the CPU reads back.
+
+#import bevy_line_boil::common::line_boil_clip_position
+
+// Must match `ReadbackParams` in readback.rs
+struct ReadbackParams {
+    world_from_local: mat4x4<f32>,
+    clip_from_world: mat4x4<f32>,
+    world_from_clip: mat4x4<f32>,
+    vertex_count: u32,
+    instance_seed: f32,
+}
+
+// Local position with the boil area in w, local normal with the boil crease in w, and
+// the mask weight in x of `mask`
+struct ReadbackVertex {
+    position: vec4<f32>,
+    normal: vec4<f32>,
+    mask: vec4<f32>,
+}
+
+@group(0) @binding(0) var<uniform> params: ReadbackParams;
+@group(0) @binding(1) var<storage, read> vertices: array<ReadbackVertex>;
+// Element 0 is a header set to (1, vertex_count) once written, followed by one position each
+@group(0) @binding(2) var<storage, read_write> displaced: array<vec4<f32>>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if index == 0u {
+        displaced[0] = vec4<f32>(1.0, f32(params.vertex_count), 0.0, 0.0);
+    }
+    if index >= params.vertex_count {
+        return;
+    }
+
+    let vertex = vertices[index];
+    let world_position = params.world_from_local * vec4<f32>(vertex.position.xyz, 1.0);
+    let clip_position = line_boil_clip_position(
+        world_position.xyz,
+        vertex.position.xyz,
+        vertex.normal.xyz,
+        params.world_from_local,
+        params.clip_from_world,
+        vertex.position.w,
+        vertex.normal.w,
+        vertex.mask.x,
+        params.instance_seed
+    );
+
+    // Back to world space at the vertex's own depth
+    let displaced_world = params.world_from_clip * clip_position;
+    displaced[index + 1u] = vec4<f32>(displaced_world.xyz / displaced_world.w, 1.0);
+}
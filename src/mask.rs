@@ -0,0 +1,66 @@
+//! Painted per-vertex weights used by [`LineBoilSettings::mask_attribute`](crate::LineBoilSettings::mask_attribute).
+
+use bevy::{
+    mesh::{MeshVertexAttribute, VertexAttributeValues, VertexFormat},
+    prelude::*,
+};
+
+/// Vertex attribute holding how strongly each vertex boils, from 0 (anchored) to 1 (full
+/// displacement), for [`MaskAttribute::BoilMask`].
+///
+/// Never inserted by the plugin: meshes supply it themselves, e.g. painted in a DCC tool.
+pub const ATTRIBUTE_BOIL_MASK: MeshVertexAttribute =
+    MeshVertexAttribute::new("LineBoil_Mask", 988_540_919, VertexFormat::Float32);
+
+/// Shader location of the mask weights in the line boil vertex shaders.
+pub const ATTRIBUTE_BOIL_MASK_LOCATION: u32 = 19;
+
+/// Vertex attribute read by
+/// [`LineBoilSettings::mask_attribute`](crate::LineBoilSettings::mask_attribute).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum MaskAttribute {
+    /// The red channel of [`Mesh::ATTRIBUTE_COLOR`].
+    ColorRed,
+    /// The green channel of [`Mesh::ATTRIBUTE_COLOR`].
+    ColorGreen,
+    /// The blue channel of [`Mesh::ATTRIBUTE_COLOR`].
+    ColorBlue,
+    /// The alpha channel of [`Mesh::ATTRIBUTE_COLOR`].
+    ColorAlpha,
+    /// [`ATTRIBUTE_BOIL_MASK`].
+    BoilMask,
+}
+
+impl MaskAttribute {
+    /// Returns the vertex attribute the weights are read from.
+    pub fn attribute(self) -> MeshVertexAttribute {
+        match self {
+            Self::BoilMask => ATTRIBUTE_BOIL_MASK,
+            _ => Mesh::ATTRIBUTE_COLOR,
+        }
+    }
+
+    /// Returns the index of the color channel the weights are read from, or `None` for
+    /// [`BoilMask`](Self::BoilMask).
+    pub fn channel(self) -> Option<u32> {
+        match self {
+            Self::ColorRed => Some(0),
+            Self::ColorGreen => Some(1),
+            Self::ColorBlue => Some(2),
+            Self::ColorAlpha => Some(3),
+            Self::BoilMask => None,
+        }
+    }
+
+    /// Returns the weight of every vertex of `mesh`, or `None` if it lacks the attribute or
+    /// its data was unloaded from the main world.
+    pub(crate) fn weights(self, mesh: &Mesh) -> Option<Vec<f32>> {
+        match (mesh.try_attribute_option(self.attribute()).ok()??, self.channel()) {
+            (VertexAttributeValues::Float32(values), None) => Some(values.clone()),
+            (VertexAttributeValues::Float32x4(values), Some(channel)) => {
+                Some(values.iter().map(|color| color[channel as usize]).collect())
+            }
+            _ => None,
+        }
+    }
+}
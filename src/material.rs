@@ -0,0 +1,694 @@
+//! The line boil material extension and its parameters.
+
+use std::any::TypeId;
+
+use bevy::{
+    mesh::MeshVertexBufferLayoutRef,
+    pbr::{MaterialExtension, MaterialExtensionKey, MaterialExtensionPipeline, MeshPipelineKey},
+    prelude::*,
+    render::render_resource::{
+        AsBindGroup, RenderPipelineDescriptor, ShaderType, SpecializedMeshPipelineError,
+    },
+    shader::{ShaderDefVal, ShaderRef},
+};
+
+use crate::{
+    ATTRIBUTE_BOIL_AREA, ATTRIBUTE_BOIL_AREA_LOCATION, ATTRIBUTE_BOIL_CREASE,
+    ATTRIBUTE_BOIL_CREASE_LOCATION, ATTRIBUTE_BOIL_MASK_LOCATION,
+    LINE_BOIL_FRAGMENT_SHADER_HANDLE, LINE_BOIL_PREPASS_SHADER_HANDLE, LINE_BOIL_SHADER_HANDLE,
+    LineBoilPath, MaskAttribute, noise,
+};
+
+/// Parameters of the line boil effect.
+///
+/// Converted into the vertex shader's uniform, and mirrored on the CPU by [`boil_noise`](crate::boil_noise).
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Default)]
+pub struct LineBoilSettings {
+    /// How far vertices move, in normalized device coordinates (world units in
+    /// [`DisplacementSpace::World`]).
+    pub intensity: f32,
+    /// Frames per second of the time quantization (lower = more "held").
+    pub frame_rate: f32,
+    /// Fraction of each held frame, at its end, spent easing into the next frame's pattern
+    /// instead of snapping to it, so fast-moving meshes don't tear at low frame rates. 0
+    /// snaps like a classic boil; around 0.5 holds each frame for half its length, then
+    /// slides over. Never moves past the next frame's pattern; clamped to 1.
+    pub frame_blend: f32,
+    /// Seconds added to the boil time before it is quantized, shifting when this entity's
+    /// held frames land without changing its pattern (unlike [`seed`](Self::seed)). E.g.
+    /// increasing offsets along a row of identical props ripple the boil down the line.
+    /// Applied on top of a [`LineBoilGroup`](crate::LineBoilGroup)'s shared time, so it
+    /// moves a member off the group's beat.
+    pub time_offset: f32,
+    /// Spatial frequency of the turbulence, per normalized device coordinate unit (per world
+    /// unit in [`DisplacementSpace::World`]).
+    pub noise_frequency: f32,
+    /// Noise function the turbulence is sampled from.
+    pub noise_kind: NoiseKind,
+    /// Per-axis spatial frequency of the turbulence along the object's local X, Y and Z
+    /// axes, for directional boil such as wood grain (high along the grain, low across it).
+    /// Zero falls back to the isotropic [`noise_frequency`](Self::noise_frequency). Has no
+    /// effect with [`clip_space_shake`](Self::clip_space_shake).
+    pub noise_frequency_axes: Vec3,
+    /// How strongly a second, slowly drifting low-frequency noise modulates the intensity
+    /// across the surface, so some regions briefly boil more frantically while others calm
+    /// down. Local intensity ranges over `1 ± intensity_noise_strength` times
+    /// [`intensity`](Self::intensity); 0 boils uniformly.
+    pub intensity_noise_strength: f32,
+    /// Spatial frequency of the intensity modulation, in the same units as
+    /// [`noise_frequency`](Self::noise_frequency) and typically well below it.
+    pub intensity_noise_frequency: f32,
+    /// View depth in world units at which the boil starts fading out with distance from the
+    /// camera, reaching zero at [`falloff_end`](Self::falloff_end).
+    pub falloff_start: f32,
+    /// View depth in world units beyond which the boil is fully faded out, so distant
+    /// objects don't shimmer sub-pixel. Intensity eases smoothly between
+    /// [`falloff_start`](Self::falloff_start) and this depth. 0 disables the falloff.
+    /// Orthographic views have no depth falloff.
+    pub falloff_end: f32,
+    /// Space the noise is sampled and vertices are displaced in.
+    pub displacement_space: DisplacementSpace,
+    /// Offset into the noise field, for variation between entities.
+    pub seed: f32,
+    /// Rate in seed units per second at which the seed advances, so the pattern keeps
+    /// reinventing itself instead of repeating. Applied on held frames only.
+    pub seed_drift: f32,
+    /// How strongly intensity scales with local triangle size, so large and small triangles
+    /// read consistently. 0 disables it; 1 makes displacement proportional to triangle size.
+    pub area_response: f32,
+    /// How strongly displacement is reduced at hard edges, keeping mechanical silhouettes
+    /// crisp while smooth areas boil. 0 disables it; 1 holds hard edges rigid. See
+    /// [`ATTRIBUTE_BOIL_CREASE`] for how edges are detected.
+    pub crease_preservation: f32,
+    /// Vertex attribute scaling displacement per vertex, so artists can paint which parts
+    /// boil, e.g. keeping a character's feet planted: 0 holds a vertex still, 1 boils it
+    /// fully. Meshes without the attribute boil everywhere. `None` disables it.
+    pub mask_attribute: Option<MaskAttribute>,
+    /// Minimum corner of an object-space box the boil is limited to, e.g. the part of a
+    /// large terrain around one feature. Vertices outside the box hold still. The box is
+    /// disabled (everything boils) unless [`region_max`](Self::region_max) is greater on
+    /// every axis. Has no effect with
+    /// [`clip_space_shake`](Self::clip_space_shake).
+    pub region_min: Vec3,
+    /// Maximum corner of the box started by [`region_min`](Self::region_min).
+    pub region_max: Vec3,
+    /// Width in object units of the soft edge of the [`region_min`](Self::region_min) box,
+    /// centred on its boundary: displacement fades from full at half the margin inside the
+    /// box to none at half the margin outside, so there's no seam. 0 cuts off at the
+    /// boundary.
+    pub region_margin: f32,
+    /// Thickness (in object units) of double-sided sheets such as capes and flags, modelled
+    /// as two layers facing opposite ways. When non-zero, noise is sampled on the midsurface
+    /// between the layers so both displace identically instead of into each other. Requires
+    /// vertex normals; 0 disables it.
+    pub sheet_thickness: f32,
+    /// Makes a [`LineBoil`](crate::LineBoil) root a crowd: each of its children is one member
+    /// (e.g. one character scene). Like all meshes of a root, they share one material per
+    /// source material, so the whole crowd batches into few draws, skinned members included
+    /// where storage buffers are available. Each member still boils individually: its mesh instances' [`MeshTag`](bevy::mesh::MeshTag) is
+    /// added to [`seed`](Self::seed), and the plugin tags untagged meshes with their member.
+    pub per_instance_seed: bool,
+    /// Object-space axis across which the boil is mirrored, so symmetric models wobble as
+    /// mirror images rather than independently per side. `None` disables mirroring.
+    pub mirror_axis: Option<MirrorAxis>,
+    /// Shakes the whole object as a flat unit on screen, like the paper under a camera stand
+    /// vibrating, instead of wobbling each vertex. Every vertex gets the same clip-space
+    /// offset per held frame (the noise sampled at the origin, see
+    /// [`boil_noise`](crate::boil_noise)), so [`area_response`](Self::area_response),
+    /// [`crease_preservation`](Self::crease_preservation) and
+    /// [`mirror_axis`](Self::mirror_axis) have no effect.
+    pub clip_space_shake: bool,
+    /// World-space direction of a constant lean added on top of the noise, e.g. pointing
+    /// away from an explosion. Normalized on upload; zero disables it.
+    pub bias_direction: Vec3,
+    /// Distance in world units the whole object is pushed along
+    /// [`bias_direction`](Self::bias_direction).
+    pub bias_strength: f32,
+    /// Strength of a fine, high-frequency shading normal perturbation that re-rolls each
+    /// held frame, like pencil grain catching the light on close-ups. Only affects lighting,
+    /// not geometry; 0 leaves shading unchanged. Forward rendering only, and only on
+    /// [`StandardMaterial`] bases: it replaces the fragment shader, so other base materials
+    /// ignore it (with a warning on conversion).
+    pub detail_normal_strength: f32,
+    /// Extra displacement of glowing materials, for boiling neon edges: intensity is scaled
+    /// by up to `1 + emissive_boil` with the brightness of the base material's emissive color
+    /// (full at a luminance of 1). 0 boils emissive and non-emissive materials alike.
+    pub emissive_boil: f32,
+    /// Strength of a flicker of the emitted light that re-rolls each held frame, in patches
+    /// across the surface, so glowing parts pulse with the boil. Emissive textures are
+    /// included. 0 leaves emission unchanged. Forward rendering only, and like
+    /// [`detail_normal_strength`](Self::detail_normal_strength) ignored by base materials
+    /// other than [`StandardMaterial`].
+    pub emissive_pulse: f32,
+    /// Also displaces the mesh in shadow passes, so shadows boil with it. Camera prepasses
+    /// (depth, normal, motion vector and deferred) always boil, exactly like the main pass,
+    /// so prepass outlines follow the boiled geometry either way. In screen space, shadow
+    /// views displace in the light's own screen: the noise and
+    /// [`intensity`](Self::intensity) are measured across the light's view, so a wide shadow
+    /// cascade turns a small intensity into a large shift and shadows detach from the mesh.
+    /// Off by default for that reason; pair it with [`DisplacementSpace::World`], where
+    /// shadows match the mesh. When false, shadows are cast from the steady, undisplaced
+    /// geometry. Shadow views share their pipelines with depth-only camera prepasses without
+    /// MSAA, which therefore follow this setting too.
+    pub boil_shadows: bool,
+    /// Recomputes shading normals from the displaced surface, so lighting on curved
+    /// surfaces wobbles along with the silhouette. The displacement is sampled at two nearby
+    /// points along the surface (following the mesh tangents when present) and the normal
+    /// rebuilt from them, at roughly three times the vertex cost. Requires vertex normals;
+    /// has no effect with [`clip_space_shake`](Self::clip_space_shake). Forward rendering
+    /// only.
+    pub recompute_normals: bool,
+}
+
+impl LineBoilSettings {
+    /// Very gentle wobble.
+    pub const SUBTLE: Self = Self {
+        intensity: 0.008,
+        frame_rate: 8.0,
+        frame_blend: 0.0,
+        time_offset: 0.0,
+        noise_frequency: 6.0,
+        noise_kind: NoiseKind::Value,
+        noise_frequency_axes: Vec3::ZERO,
+        intensity_noise_strength: 0.0,
+        intensity_noise_frequency: 0.0,
+        falloff_start: 0.0,
+        falloff_end: 0.0,
+        displacement_space: DisplacementSpace::Screen,
+        seed: 0.0,
+        seed_drift: 0.0,
+        area_response: 0.0,
+        crease_preservation: 0.0,
+        mask_attribute: None,
+        region_min: Vec3::ZERO,
+        region_max: Vec3::ZERO,
+        region_margin: 0.0,
+        sheet_thickness: 0.0,
+        per_instance_seed: false,
+        mirror_axis: None,
+        clip_space_shake: false,
+        bias_direction: Vec3::ZERO,
+        bias_strength: 0.0,
+        detail_normal_strength: 0.0,
+        emissive_boil: 0.0,
+        emissive_pulse: 0.0,
+        boil_shadows: false,
+        recompute_normals: false,
+    };
+
+    /// More pronounced effect.
+    pub const AGGRESSIVE: Self = Self {
+        intensity: 0.04,
+        frame_rate: 4.0,
+        frame_blend: 0.0,
+        time_offset: 0.0,
+        noise_frequency: 12.0,
+        noise_kind: NoiseKind::Value,
+        noise_frequency_axes: Vec3::ZERO,
+        intensity_noise_strength: 0.0,
+        intensity_noise_frequency: 0.0,
+        falloff_start: 0.0,
+        falloff_end: 0.0,
+        displacement_space: DisplacementSpace::Screen,
+        seed: 0.0,
+        seed_drift: 0.0,
+        area_response: 0.0,
+        crease_preservation: 0.0,
+        mask_attribute: None,
+        region_min: Vec3::ZERO,
+        region_max: Vec3::ZERO,
+        region_margin: 0.0,
+        sheet_thickness: 0.0,
+        per_instance_seed: false,
+        mirror_axis: None,
+        clip_space_shake: false,
+        bias_direction: Vec3::ZERO,
+        bias_strength: 0.0,
+        detail_normal_strength: 0.0,
+        emissive_boil: 0.0,
+        emissive_pulse: 0.0,
+        boil_shadows: false,
+        recompute_normals: false,
+    };
+}
+
+impl Default for LineBoilSettings {
+    fn default() -> Self {
+        Self::SUBTLE
+    }
+}
+
+/// Noise function for [`LineBoilSettings::noise_kind`].
+///
+/// All kinds read [`noise_frequency`](LineBoilSettings::noise_frequency) and
+/// [`seed`](LineBoilSettings::seed) the same way and displace by up to
+/// [`intensity`](LineBoilSettings::intensity) per axis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Reflect)]
+pub enum NoiseKind {
+    /// Smooth value noise. Cheapest, with a faintly grid-aligned character.
+    #[default]
+    Value,
+    /// Simplex noise, without the grid alignment, at a cost similar to
+    /// [`Value`](Self::Value).
+    Simplex,
+    /// Curl of value noise: a divergence-free, swirling displacement like ink bleeding into
+    /// paper. About four times the cost of [`Value`](Self::Value).
+    Curl,
+}
+
+/// Space of [`LineBoilSettings::displacement_space`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Reflect)]
+pub enum DisplacementSpace {
+    /// Noise is sampled at the vertex's position on screen and vertices wobble across the
+    /// screen, like lines drawn on paper. The wobble looks the same size at any distance,
+    /// and moving through the world doesn't change the pattern.
+    #[default]
+    Screen,
+    /// Noise is sampled at the vertex's world position and vertices are displaced in world
+    /// units, so the wobble is attached to the object: it shrinks with distance, keeps the
+    /// same size however the entity is scaled, and only changes on held frames as the
+    /// camera moves. Like the screen-space mode, neighbouring meshes with the same settings
+    /// sample one continuous field, so the parts of a rigid prop boil together rather than
+    /// independently. Keep boiled objects within a few thousand units of the origin, where
+    /// the noise has enough precision.
+    World,
+}
+
+/// Object-space axis for [`LineBoilSettings::mirror_axis`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum MirrorAxis {
+    /// Mirror across the YZ plane (left/right symmetry for most character rigs).
+    X,
+    /// Mirror across the XZ plane.
+    Y,
+    /// Mirror across the XY plane.
+    Z,
+}
+
+impl MirrorAxis {
+    /// Returns the unit axis in object space.
+    pub fn to_vec3(self) -> Vec3 {
+        match self {
+            MirrorAxis::X => Vec3::X,
+            MirrorAxis::Y => Vec3::Y,
+            MirrorAxis::Z => Vec3::Z,
+        }
+    }
+}
+
+/// Shader location of the mesh normal in `line_boil_prepass.wgsl` when the prepass itself
+/// doesn't bind normals.
+const PREPASS_NORMAL_LOCATION: u32 = 18;
+
+/// The line boil material extension.
+#[derive(Asset, AsBindGroup, Reflect, Debug, Clone)]
+#[reflect(Default)]
+#[uniform(100, LineBoilUniform)]
+#[bind_group_data(LineBoilMaterialKey)]
+pub struct LineBoilMaterial {
+    /// Effect parameters, uploaded at `@binding(100)`.
+    pub settings: LineBoilSettings,
+    /// Per-frame state written by the plugin, uploaded alongside the settings. Hidden from
+    /// reflection, as the plugin overwrites it every frame.
+    #[reflect(ignore)]
+    pub(crate) runtime: LineBoilRuntime,
+}
+
+impl LineBoilMaterial {
+    /// Creates the extension for `settings`.
+    pub fn new(settings: LineBoilSettings) -> Self {
+        Self {
+            settings,
+            runtime: LineBoilRuntime {
+                time: 0.0,
+                intensity: settings.intensity,
+                frame_rate: settings.frame_rate,
+                noise_frequency: settings.noise_frequency,
+                seed_offset: 0.0,
+                path: LineBoilPathUniform::default(),
+                custom_base: false,
+            },
+        }
+    }
+
+    /// Records the base material type `B` the extension is put on. The fragment-side options
+    /// rely on [`StandardMaterial`]'s fragment shader and bindings, so other bases skip them.
+    pub(crate) fn on_base<B: 'static>(mut self) -> Self {
+        self.runtime.custom_base = TypeId::of::<B>() != TypeId::of::<StandardMaterial>();
+        let settings = &self.settings;
+        if self.runtime.custom_base
+            && (settings.detail_normal_strength != 0.0 || settings.emissive_pulse != 0.0)
+        {
+            warn_once!(
+                "LineBoil: detail_normal_strength and emissive_pulse only apply to \
+                 StandardMaterial bases, ignoring them for {}",
+                std::any::type_name::<B>()
+            );
+        }
+        self
+    }
+}
+
+impl Default for LineBoilMaterial {
+    fn default() -> Self {
+        Self::new(LineBoilSettings::default())
+    }
+}
+
+/// Runtime state of a [`LineBoilMaterial`], kept apart from its authored settings.
+///
+/// Holds the effective values after active windows, drives, kicks and global scale, which
+/// the plugin rewrites every frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct LineBoilRuntime {
+    /// Shader time in seconds.
+    pub time: f32,
+    /// Effective intensity, also the starting point of the next frame's rate limiting.
+    pub intensity: f32,
+    /// Effective frame rate.
+    pub frame_rate: f32,
+    /// Effective noise frequency.
+    pub noise_frequency: f32,
+    /// Added to the seed, e.g. for members of a [`LineBoilGroup`](crate::LineBoilGroup).
+    pub seed_offset: f32,
+    /// The root's [`LineBoilPath`], if any.
+    pub path: LineBoilPathUniform,
+    /// Whether the base material isn't a [`StandardMaterial`], see
+    /// [`LineBoilMaterial::on_base`].
+    pub custom_base: bool,
+}
+
+/// GPU form of a [`LineBoilPath`]; `point_count` below 2 disables it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct LineBoilPathUniform {
+    pub points: [Vec4; LineBoilPath::MAX_POINTS],
+    pub point_count: u32,
+    pub flow_speed: f32,
+    pub alignment: f32,
+}
+
+impl From<&LineBoilPath> for LineBoilPathUniform {
+    fn from(path: &LineBoilPath) -> Self {
+        let mut points = [Vec4::ZERO; LineBoilPath::MAX_POINTS];
+        for (point, control_point) in points.iter_mut().zip(&path.points) {
+            *point = control_point.extend(1.0);
+        }
+        Self {
+            points,
+            point_count: path.points.len().min(LineBoilPath::MAX_POINTS) as u32,
+            flow_speed: path.flow_speed,
+            alignment: path.alignment.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// GPU layout of [`LineBoilSettings`] and [`LineBoilRuntime`]. Must match `LineBoilSettings` in `line_boil_common.wgsl`.
+#[derive(ShaderType)]
+pub(crate) struct LineBoilUniform {
+    intensity: f32,
+    frame_rate: f32,
+    noise_frequency: f32,
+    seed: f32,
+    seed_drift: f32,
+    area_response: f32,
+    time: f32,
+    detail_normal_strength: f32,
+    mirror_axis: Vec3,
+    bias: Vec3,
+    crease_preservation: f32,
+    sheet_thickness: f32,
+    noise_frequency_axes: Vec3,
+    emissive_pulse: f32,
+    path_point_count: u32,
+    path_flow_speed: f32,
+    path_alignment: f32,
+    path_points: [Vec4; LineBoilPath::MAX_POINTS],
+    intensity_noise_strength: f32,
+    intensity_noise_frequency: f32,
+    falloff_start: f32,
+    falloff_end: f32,
+    frame_blend: f32,
+    region_min: Vec3,
+    region_max: Vec3,
+    region_margin: f32,
+    seed_drift_offset: f32,
+}
+
+impl From<&LineBoilMaterial> for LineBoilUniform {
+    fn from(material: &LineBoilMaterial) -> Self {
+        let settings = &material.settings;
+        let runtime = &material.runtime;
+        let time = runtime.time + settings.time_offset;
+        Self {
+            intensity: runtime.intensity,
+            frame_rate: runtime.frame_rate,
+            noise_frequency: runtime.noise_frequency,
+            seed: settings.seed + runtime.seed_offset,
+            seed_drift: settings.seed_drift,
+            area_response: settings.area_response,
+            time,
+            detail_normal_strength: settings.detail_normal_strength,
+            mirror_axis: settings.mirror_axis.map_or(Vec3::ZERO, MirrorAxis::to_vec3),
+            bias: settings.bias_direction.normalize_or_zero() * settings.bias_strength,
+            crease_preservation: settings.crease_preservation,
+            sheet_thickness: settings.sheet_thickness,
+            noise_frequency_axes: settings.noise_frequency_axes,
+            emissive_pulse: settings.emissive_pulse,
+            path_point_count: runtime.path.point_count,
+            path_flow_speed: runtime.path.flow_speed,
+            path_alignment: runtime.path.alignment,
+            path_points: runtime.path.points,
+            intensity_noise_strength: settings.intensity_noise_strength,
+            intensity_noise_frequency: settings.intensity_noise_frequency,
+            falloff_start: settings.falloff_start,
+            falloff_end: settings.falloff_end,
+            frame_blend: settings.frame_blend,
+            region_min: settings.region_min,
+            region_max: settings.region_max,
+            region_margin: settings.region_margin,
+            seed_drift_offset: noise::seed_drift_offset(
+                time,
+                runtime.frame_rate,
+                settings.seed_drift,
+            ),
+        }
+    }
+}
+
+/// Pipeline key of [`LineBoilMaterial`], selecting the shader defs it is specialized with.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct LineBoilMaterialKey {
+    area_response: bool,
+    crease: bool,
+    mask: Option<MaskAttribute>,
+    region: bool,
+    thin_sheet: bool,
+    noise_kind: NoiseKind,
+    frame_blend: bool,
+    noise_axes: bool,
+    intensity_noise: bool,
+    falloff: bool,
+    world_space: bool,
+    instance_seed: bool,
+    path: bool,
+    mirror: bool,
+    clip_space_shake: bool,
+    detail_normal: bool,
+    emissive_pulse: bool,
+    boil_shadows: bool,
+    recompute_normals: bool,
+}
+
+impl From<&LineBoilMaterial> for LineBoilMaterialKey {
+    fn from(material: &LineBoilMaterial) -> Self {
+        let settings = &material.settings;
+        // Both swap in a fragment shader written for `StandardMaterial`.
+        let custom_base = material.runtime.custom_base;
+        Self {
+            area_response: settings.area_response != 0.0 && !settings.clip_space_shake,
+            crease: settings.crease_preservation != 0.0 && !settings.clip_space_shake,
+            mask: settings.mask_attribute,
+            region: settings.region_min.cmplt(settings.region_max).all()
+                && !settings.clip_space_shake,
+            thin_sheet: settings.sheet_thickness != 0.0 && !settings.clip_space_shake,
+            noise_kind: settings.noise_kind,
+            frame_blend: settings.frame_blend > 0.0,
+            noise_axes: settings.noise_frequency_axes != Vec3::ZERO
+                && !settings.clip_space_shake,
+            intensity_noise: settings.intensity_noise_strength != 0.0,
+            falloff: settings.falloff_end > 0.0,
+            world_space: settings.displacement_space == DisplacementSpace::World,
+            instance_seed: settings.per_instance_seed,
+            path: material.runtime.path.point_count >= 2 && !settings.clip_space_shake,
+            mirror: settings.mirror_axis.is_some() && !settings.clip_space_shake,
+            clip_space_shake: settings.clip_space_shake,
+            detail_normal: settings.detail_normal_strength != 0.0 && !custom_base,
+            emissive_pulse: settings.emissive_pulse != 0.0 && !custom_base,
+            boil_shadows: settings.boil_shadows,
+            recompute_normals: settings.recompute_normals && !settings.clip_space_shake,
+        }
+    }
+}
+
+impl LineBoilMaterialKey {
+    /// Shader defs selecting the displacement features of `line_boil_common.wgsl`, for a mesh
+    /// with or without the boil area and crease attributes.
+    pub(crate) fn displacement_shader_defs(
+        &self,
+        has_area: bool,
+        has_crease: bool,
+    ) -> Vec<ShaderDefVal> {
+        [
+            (self.area_response && has_area, "LINE_BOIL_AREA_RESPONSE"),
+            (self.crease && has_crease, "LINE_BOIL_CREASE"),
+            (self.region, "LINE_BOIL_REGION"),
+            (self.thin_sheet, "LINE_BOIL_THIN_SHEET"),
+            (self.noise_kind == NoiseKind::Simplex, "LINE_BOIL_NOISE_SIMPLEX"),
+            (self.noise_kind == NoiseKind::Curl, "LINE_BOIL_NOISE_CURL"),
+            (self.frame_blend, "LINE_BOIL_FRAME_BLEND"),
+            (self.noise_axes, "LINE_BOIL_NOISE_AXES"),
+            (self.intensity_noise, "LINE_BOIL_INTENSITY_NOISE"),
+            (self.falloff, "LINE_BOIL_FALLOFF"),
+            (self.world_space, "LINE_BOIL_WORLD_SPACE"),
+            (self.instance_seed, "LINE_BOIL_INSTANCE_SEED"),
+            (self.path, "LINE_BOIL_PATH"),
+            (self.mirror, "LINE_BOIL_MIRROR"),
+            (self.clip_space_shake, "LINE_BOIL_CLIP_SHAKE"),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, def)| def.into())
+        .collect()
+    }
+}
+
+/// Binds the mask weights selected by `key` at [`ATTRIBUTE_BOIL_MASK_LOCATION`] and enables
+/// them in the vertex shader. Only bound when the mesh has the attribute; other meshes boil
+/// everywhere.
+pub(crate) fn bind_boil_mask(
+    descriptor: &mut RenderPipelineDescriptor,
+    layout: &MeshVertexBufferLayoutRef,
+    key: &LineBoilMaterialKey,
+) -> Result<(), SpecializedMeshPipelineError> {
+    let Some(mask) = key.mask.filter(|mask| layout.0.contains(mask.attribute())) else {
+        return Ok(());
+    };
+    let mask_layout = layout.0.get_layout(&[mask
+        .attribute()
+        .at_shader_location(ATTRIBUTE_BOIL_MASK_LOCATION)])?;
+    descriptor.vertex.buffers[0]
+        .attributes
+        .extend(mask_layout.attributes);
+    descriptor.vertex.shader_defs.push("LINE_BOIL_MASK".into());
+    if let Some(channel) = mask.channel() {
+        descriptor
+            .vertex
+            .shader_defs
+            .push(ShaderDefVal::UInt("LINE_BOIL_MASK_CHANNEL".into(), channel));
+    }
+    Ok(())
+}
+
+impl MaterialExtension for LineBoilMaterial {
+    fn vertex_shader() -> ShaderRef {
+        ShaderRef::Handle(LINE_BOIL_SHADER_HANDLE)
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        ShaderRef::Default
+    }
+
+    fn prepass_vertex_shader() -> ShaderRef {
+        ShaderRef::Handle(LINE_BOIL_PREPASS_SHADER_HANDLE)
+    }
+
+    fn specialize(
+        _pipeline: &MaterialExtensionPipeline,
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        key: MaterialExtensionKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let main_pass = descriptor.vertex.shader == LINE_BOIL_SHADER_HANDLE;
+        // Shadow views are depth-only prepasses without MSAA; without `boil_shadows`, they run
+        // the prepass shader undisplaced. Camera prepasses always boil.
+        let shadow_view = !key.mesh_key.intersects(
+            MeshPipelineKey::NORMAL_PREPASS
+                | MeshPipelineKey::MOTION_VECTOR_PREPASS
+                | MeshPipelineKey::DEFERRED_PREPASS,
+        ) && key.mesh_key.msaa_samples() == 1;
+        let prepass = descriptor.vertex.shader == LINE_BOIL_PREPASS_SHADER_HANDLE
+            && (!shadow_view || key.bind_group_data.boil_shadows);
+        if !main_pass && !prepass {
+            return Ok(());
+        }
+        if prepass {
+            descriptor.vertex.shader_defs.push("LINE_BOIL_PREPASS".into());
+        }
+
+        let key_data = key.bind_group_data;
+        let has_area = layout.0.contains(ATTRIBUTE_BOIL_AREA);
+        let has_crease = layout.0.contains(ATTRIBUTE_BOIL_CREASE);
+        if key_data.area_response && has_area {
+            let area_layout = layout.0.get_layout(&[
+                ATTRIBUTE_BOIL_AREA.at_shader_location(ATTRIBUTE_BOIL_AREA_LOCATION)
+            ])?;
+            descriptor.vertex.buffers[0]
+                .attributes
+                .extend(area_layout.attributes);
+        }
+        if key_data.crease && has_crease {
+            let crease_layout = layout.0.get_layout(&[
+                ATTRIBUTE_BOIL_CREASE.at_shader_location(ATTRIBUTE_BOIL_CREASE_LOCATION)
+            ])?;
+            descriptor.vertex.buffers[0]
+                .attributes
+                .extend(crease_layout.attributes);
+        }
+        bind_boil_mask(descriptor, layout, &key_data)?;
+        // Shadow and depth-only prepasses don't read normals, so bind them separately.
+        if key_data.thin_sheet
+            && prepass
+            && layout.0.contains(Mesh::ATTRIBUTE_NORMAL)
+            && !descriptor
+                .vertex
+                .shader_defs
+                .contains(&"NORMAL_PREPASS_OR_DEFERRED_PREPASS".into())
+        {
+            let normal_layout = layout.0.get_layout(&[
+                Mesh::ATTRIBUTE_NORMAL.at_shader_location(PREPASS_NORMAL_LOCATION)
+            ])?;
+            descriptor.vertex.buffers[0]
+                .attributes
+                .extend(normal_layout.attributes);
+            descriptor.vertex.shader_defs.push("LINE_BOIL_PREPASS_NORMAL".into());
+        }
+        descriptor
+            .vertex
+            .shader_defs
+            .extend(key_data.displacement_shader_defs(has_area, has_crease));
+        if main_pass && key_data.recompute_normals {
+            descriptor
+                .vertex
+                .shader_defs
+                .push("LINE_BOIL_RECOMPUTE_NORMALS".into());
+        }
+        // Swapped in per pipeline so materials without grain or pulse keep the default
+        // fragment shader.
+        if main_pass
+            && (key_data.detail_normal || key_data.emissive_pulse)
+            && let Some(fragment) = descriptor.fragment.as_mut()
+        {
+            fragment.shader = LINE_BOIL_FRAGMENT_SHADER_HANDLE;
+            if key_data.detail_normal {
+                fragment.shader_defs.push("LINE_BOIL_DETAIL_NORMAL".into());
+            }
+            if key_data.emissive_pulse {
+                fragment.shader_defs.push("LINE_BOIL_EMISSIVE_PULSE".into());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+//! Line boil for 2D meshes with a [`ColorMaterial`].
+
+use bevy::{
+    asset::load_internal_asset,
+    ecs::system::SystemParamItem,
+    mesh::MeshVertexBufferLayoutRef,
+    prelude::*,
+    render::{
+        render_resource::{
+            AsBindGroup, AsBindGroupError, BindGroupLayout, BindGroupLayoutEntry,
+            RenderPipelineDescriptor, SpecializedMeshPipelineError, UnpreparedBindGroup,
+        },
+        renderer::RenderDevice,
+    },
+    shader::{ShaderDefVal, ShaderRef},
+    sprite_render::{AlphaMode2d, Material2d, Material2dKey, Material2dPlugin},
+};
+
+use crate::{
+    LINE_BOIL_2D_SHADER_HANDLE, LineBoilAsset, LineBoilCorePlugin,
+    LineBoilMaterial, LineBoilMaterialKey, LineBoilPlugin, LineBoilSystems, LineBoilTarget,
+    add_line_boil_conversion, material::bind_boil_mask, sync_line_boil_settings,
+    update_line_boil_time,
+};
+
+/// Plugin that adds line boil support for 2D meshes.
+///
+/// [`Mesh2d`]s with a [`ColorMaterial`] under a [`LineBoil`](crate::LineBoil) root are
+/// converted to [`LineBoilMaterial2d`], with the same settings, presets and time controls
+/// (clock, groups, drives, kicks) as 3D meshes:
+///
+/// ```rust,ignore
+/// app.add_plugins((LineBoilPlugin::default(), LineBoil2dPlugin));
+///
+/// commands.spawn((
+///     Mesh2d(meshes.add(Circle::new(50.0))),
+///     MeshMaterial2d(materials.add(Color::WHITE)),
+///     LineBoil::subtle(),
+/// ));
+/// ```
+///
+/// Displacement stays in the 2D plane:
+/// [`DisplacementSpace::World`](crate::DisplacementSpace::World) falls back to screen space,
+/// and settings reading 3D-only data (area response, crease preservation, lighting and
+/// shadows) have no effect. Add it after [`LineBoilPlugin`](crate::LineBoilPlugin), whose
+/// configuration it shares; on its own it uses the default configuration.
+#[derive(Default)]
+pub struct LineBoil2dPlugin;
+
+impl Plugin for LineBoil2dPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<LineBoilCorePlugin>() {
+            let LineBoilPlugin {
+                config,
+                scene_rules,
+                ..
+            } = LineBoilPlugin::default();
+            app.add_plugins(LineBoilCorePlugin {
+                config,
+                scene_rules,
+            });
+        }
+
+        load_internal_asset!(
+            app,
+            LINE_BOIL_2D_SHADER_HANDLE,
+            "line_boil_2d.wgsl",
+            Shader::from_wgsl
+        );
+        app.add_plugins(Material2dPlugin::<LineBoilMaterial2d>::default())
+            .register_type::<LineBoilMaterial2d>();
+        add_line_boil_conversion::<LineBoilMesh2d>(app);
+        app.add_systems(
+            Update,
+            (
+                sync_line_boil_settings::<LineBoilMesh2d>,
+                update_line_boil_time::<LineBoilMesh2d>,
+            )
+                .chain()
+                .in_set(LineBoilSystems::Upload),
+        );
+    }
+}
+
+/// A [`ColorMaterial`] with the line boil displacement, what [`LineBoil2dPlugin`] converts
+/// 2D meshes to. The 2D counterpart of `ExtendedMaterial<StandardMaterial, LineBoilMaterial>`.
+#[derive(Asset, Reflect, Debug, Clone)]
+pub struct LineBoilMaterial2d {
+    /// The original material, rendered by its own fragment shader.
+    pub base: ColorMaterial,
+    /// The displacement, bound at `@binding(100)` like in 3D.
+    pub extension: LineBoilMaterial,
+}
+
+impl AsBindGroup for LineBoilMaterial2d {
+    type Data = LineBoilMaterialKey;
+    type Param = (
+        <ColorMaterial as AsBindGroup>::Param,
+        <LineBoilMaterial as AsBindGroup>::Param,
+    );
+
+    fn label() -> &'static str {
+        LineBoilMaterial::label()
+    }
+
+    fn bind_group_data(&self) -> Self::Data {
+        self.extension.bind_group_data()
+    }
+
+    fn unprepared_bind_group(
+        &self,
+        layout: &BindGroupLayout,
+        render_device: &RenderDevice,
+        (base_param, extension_param): &mut SystemParamItem<'_, '_, Self::Param>,
+        _force_non_bindless: bool,
+    ) -> Result<UnpreparedBindGroup, AsBindGroupError> {
+        // Both halves share one bind group, so neither may use bindless arrays.
+        let UnpreparedBindGroup { mut bindings } =
+            self.base
+                .unprepared_bind_group(layout, render_device, base_param, true)?;
+        let UnpreparedBindGroup {
+            bindings: extension_bindings,
+        } = self
+            .extension
+            .unprepared_bind_group(layout, render_device, extension_param, true)?;
+        bindings.extend(extension_bindings.0);
+        Ok(UnpreparedBindGroup { bindings })
+    }
+
+    fn bind_group_layout_entries(
+        render_device: &RenderDevice,
+        _force_non_bindless: bool,
+    ) -> Vec<BindGroupLayoutEntry>
+    where
+        Self: Sized,
+    {
+        let mut entries = ColorMaterial::bind_group_layout_entries(render_device, true);
+        entries.extend(LineBoilMaterial::bind_group_layout_entries(render_device, true));
+        entries
+    }
+}
+
+impl Material2d for LineBoilMaterial2d {
+    fn vertex_shader() -> ShaderRef {
+        ShaderRef::Handle(LINE_BOIL_2D_SHADER_HANDLE)
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        ColorMaterial::fragment_shader()
+    }
+
+    fn depth_bias(&self) -> f32 {
+        self.base.depth_bias()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        self.base.alpha_mode()
+    }
+
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        layout: &MeshVertexBufferLayoutRef,
+        key: Material2dKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        let key_data = key.bind_group_data;
+        bind_boil_mask(descriptor, layout, &key_data)?;
+        // 2D meshes get no area or crease attributes, and always boil in the screen plane.
+        let world_space = ShaderDefVal::from("LINE_BOIL_WORLD_SPACE");
+        descriptor.vertex.shader_defs.extend(
+            key_data
+                .displacement_shader_defs(false, false)
+                .into_iter()
+                .filter(|def| *def != world_space),
+        );
+        Ok(())
+    }
+}
+
+impl LineBoilAsset for LineBoilMaterial2d {
+    fn line_boil(&self) -> &LineBoilMaterial {
+        &self.extension
+    }
+
+    fn line_boil_mut(&mut self) -> &mut LineBoilMaterial {
+        &mut self.extension
+    }
+}
+
+/// [`LineBoilTarget`] for [`Mesh2d`]s with a [`ColorMaterial`], converted to
+/// [`LineBoilMaterial2d`].
+pub(crate) struct LineBoilMesh2d;
+
+impl LineBoilTarget for LineBoilMesh2d {
+    type Base = ColorMaterial;
+    type Boiled = LineBoilMaterial2d;
+    type Source = MeshMaterial2d<ColorMaterial>;
+    type Converted = MeshMaterial2d<LineBoilMaterial2d>;
+
+    fn boil(base: &ColorMaterial, extension: LineBoilMaterial) -> LineBoilMaterial2d {
+        LineBoilMaterial2d {
+            base: base.clone(),
+            extension,
+        }
+    }
+}
@@ -0,0 +1,256 @@
+//! CPU mirror of the turbulence noise in `line_boil_common.wgsl`.
+//!
+//! Every function here is a line-for-line port of its WGSL counterpart. Any change to the
+//! shader's noise must be mirrored here (and vice versa) so gameplay code keeps sampling the
+//! same field the GPU displaces vertices with. The constants they share are handed to the
+//! shader as shader defs by [`noise_shader_defs`].
+
+use bevy::{math::Vec3, shader::ShaderDefVal};
+
+use crate::{LineBoilSettings, NoiseKind};
+
+/// Number of held frames after which the boil pattern repeats, keeping noise inputs small.
+const TIME_WRAP_FRAMES: f32 = 4096.0;
+
+/// Per-axis scale applied to the quantized time when sampling each displacement axis.
+const AXIS_TIME_SCALES: Vec3 = Vec3::new(1.0, 1.3, 0.7);
+
+/// Per-axis offset into the noise field, decorrelating the three displacement axes.
+const AXIS_OFFSETS: [Vec3; 3] = [
+    Vec3::ZERO,
+    Vec3::new(0.0, 0.0, 100.0),
+    Vec3::new(200.0, 0.0, 0.0),
+];
+
+/// Shader defs setting the constants above in `line_boil_common.wgsl`. The axis constants
+/// are passed as the bits of their floats, so the shader gets exactly the same values.
+pub(crate) fn noise_shader_defs() -> Vec<ShaderDefVal> {
+    let float = |name: String, value: f32| ShaderDefVal::UInt(name, value.to_bits());
+    // A whole number of frames.
+    let mut defs = vec![ShaderDefVal::UInt(
+        "LINE_BOIL_TIME_WRAP_FRAMES".into(),
+        TIME_WRAP_FRAMES as u32,
+    )];
+    for (i, axis) in ["X", "Y", "Z"].into_iter().enumerate() {
+        defs.push(float(format!("LINE_BOIL_AXIS_TIME_SCALE_{axis}"), AXIS_TIME_SCALES[i]));
+        for (j, offset) in AXIS_OFFSETS.iter().enumerate() {
+            defs.push(float(format!("LINE_BOIL_AXIS_OFFSET_{j}_{axis}"), offset[i]));
+        }
+    }
+    defs
+}
+
+/// Seed units after which the accumulated seed drift wraps, keeping noise inputs small. A
+/// prime, so the drift and [`TIME_WRAP_FRAMES`] only line up again after days of play.
+const SEED_DRIFT_WRAP: f64 = 1009.0;
+
+/// Step in noise coordinates of the finite differences taken by [`NoiseKind::Curl`].
+const CURL_STEP: f32 = 0.01;
+
+/// Brings the typical size of [`NoiseKind::Curl`] in line with the other noise kinds.
+const CURL_SCALE: f32 = 0.5;
+
+/// Samples the line boil displacement at `pos`, exactly as the vertex shader does.
+///
+/// `pos` is the noise sample coordinate; the vertex shader uses the vertex's normalized
+/// device coordinates `(ndc.x, ndc.y, 0.0)`, clamped to `±16`, or its world position in
+/// [`DisplacementSpace::World`](crate::DisplacementSpace::World). `time` is the shader's
+/// time input in seconds (the plugin uploads
+/// [`LineBoilClock::elapsed`](crate::LineBoilClock::elapsed)), which is shifted by
+/// [`LineBoilSettings::time_offset`] and quantized by [`LineBoilSettings::frame_rate`] before
+/// sampling, and eased into the next held frame by [`LineBoilSettings::frame_blend`].
+///
+/// The returned vector is the displacement scaled by [`LineBoilSettings::intensity`]. The
+/// shader offsets the vertex's normalized device coordinates by its `x` and `y` components,
+/// or its world position by all three in world space.
+///
+/// Per-vertex adjustments made around the sample ([`LineBoilSettings::mirror_axis`] folding,
+/// [`LineBoilSettings::noise_frequency_axes`] stretching, [`LineBoilSettings::area_response`]
+/// scaling, [`LineBoilSettings::intensity_noise_strength`] modulation, the
+/// [`LineBoilSettings::falloff_end`] and [`LineBoilSettings::region_min`] fades, the
+/// [`LineBoilSettings::bias_direction`] lean) are not applied here.
+///
+/// [`LineBoilSettings::seed`] is used as given, while the GPU adds the root's per-entity
+/// offset: to match a root, set it to [`LineBoil::effective_seed`](crate::LineBoil::effective_seed).
+pub fn boil_noise(pos: Vec3, settings: &LineBoilSettings, time: f32) -> Vec3 {
+    // The plugin adds the offset to the time it uploads.
+    let time = time + settings.time_offset;
+    let drift_offset = seed_drift_offset(time, settings.frame_rate, settings.seed_drift);
+    let sample = |time_quantized: f32, frames_ahead: f32| {
+        let seed = drifted_seed(
+            settings.seed,
+            drift_offset,
+            settings.seed_drift,
+            frames_ahead,
+            settings.frame_rate,
+        );
+        smooth_turbulent_noise(
+            pos,
+            time_quantized,
+            settings.noise_frequency,
+            seed,
+            settings.noise_kind,
+        )
+    };
+
+    let time_quantized = quantize_time(time, settings.frame_rate);
+    let mut noise = sample(time_quantized, 0.0);
+    if settings.frame_blend > 0.0 {
+        let time_next = (time_quantized + 1.0) % TIME_WRAP_FRAMES;
+        let blend = frame_blend_weight(time, settings.frame_rate, settings.frame_blend);
+        noise = noise.lerp(sample(time_next, 1.0), blend);
+    }
+    noise * settings.intensity
+}
+
+fn hash31(p: Vec3) -> f32 {
+    let mut p3 = fract(p * 0.1031);
+    p3 += p3.dot(Vec3::new(p3.z, p3.y, p3.x) + 31.32);
+    fract_f32((p3.x + p3.y) * p3.z)
+}
+
+fn smooth_interp(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn value_noise_3d(p: Vec3) -> f32 {
+    let i = p.floor();
+    let f = fract(p);
+
+    let u = Vec3::new(smooth_interp(f.x), smooth_interp(f.y), smooth_interp(f.z));
+
+    let n000 = hash31(i + Vec3::new(0.0, 0.0, 0.0));
+    let n100 = hash31(i + Vec3::new(1.0, 0.0, 0.0));
+    let n010 = hash31(i + Vec3::new(0.0, 1.0, 0.0));
+    let n110 = hash31(i + Vec3::new(1.0, 1.0, 0.0));
+    let n001 = hash31(i + Vec3::new(0.0, 0.0, 1.0));
+    let n101 = hash31(i + Vec3::new(1.0, 0.0, 1.0));
+    let n011 = hash31(i + Vec3::new(0.0, 1.0, 1.0));
+    let n111 = hash31(i + Vec3::new(1.0, 1.0, 1.0));
+
+    let n00 = mix(n000, n100, u.x);
+    let n10 = mix(n010, n110, u.x);
+    let n01 = mix(n001, n101, u.x);
+    let n11 = mix(n011, n111, u.x);
+    let n0 = mix(n00, n10, u.y);
+    let n1 = mix(n01, n11, u.y);
+    mix(n0, n1, u.z) * 2.0 - 1.0
+}
+
+fn simplex_gradient_dot(hash: f32, offset: Vec3) -> f32 {
+    let h = ((hash * 16.0) as u32).min(15);
+    let u = if h < 8 { offset.x } else { offset.y };
+    let v = if h < 4 {
+        offset.y
+    } else if h == 12 || h == 14 {
+        offset.x
+    } else {
+        offset.z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+fn simplex_noise_3d(p: Vec3) -> f32 {
+    let i = (p + (p.x + p.y + p.z) / 3.0).floor();
+    let x0 = p - (i - (i.x + i.y + i.z) / 6.0);
+
+    let g = step(Vec3::new(x0.y, x0.z, x0.x), x0);
+    let l = 1.0 - g;
+    let l_zxy = Vec3::new(l.z, l.x, l.y);
+    let i1 = g.min(l_zxy);
+    let i2 = g.max(l_zxy);
+    let x1 = x0 - i1 + 1.0 / 6.0;
+    let x2 = x0 - i2 + 1.0 / 3.0;
+    let x3 = x0 - 0.5;
+
+    let mut sum = 0.0;
+    for (corner, offset) in [(Vec3::ZERO, x0), (i1, x1), (i2, x2), (Vec3::ONE, x3)] {
+        let w = (0.6 - offset.dot(offset)).max(0.0);
+        let w2 = w * w;
+        sum += w2 * w2 * simplex_gradient_dot(hash31(i + corner), offset);
+    }
+    (32.0 * sum).clamp(-1.0, 1.0)
+}
+
+fn boil_noise_3d(p: Vec3, kind: NoiseKind) -> f32 {
+    match kind {
+        NoiseKind::Simplex => simplex_noise_3d(p),
+        NoiseKind::Value | NoiseKind::Curl => value_noise_3d(p),
+    }
+}
+
+fn quantize_time(time: f32, fps: f32) -> f32 {
+    // WGSL `%` on floats truncates like Rust's, so `rem_euclid` is not wanted here.
+    (time * fps).floor() % TIME_WRAP_FRAMES
+}
+
+fn frame_blend_weight(time: f32, fps: f32, blend: f32) -> f32 {
+    let ease = blend.clamp(1e-4, 1.0);
+    smooth_interp(((fract_f32(time * fps) - (1.0 - ease)) / ease).clamp(0.0, 1.0))
+}
+
+/// Seed drift accumulated up to the held frame shown at `time`, uploaded by the plugin.
+///
+/// Counted from the unwrapped frame in double precision: derived from the wrapped quantized
+/// time, the seed would snap back with it and the boil repeat.
+pub(crate) fn seed_drift_offset(time: f32, fps: f32, drift: f32) -> f32 {
+    let fps = f64::from(fps.max(0.0001));
+    let frame = (f64::from(time) * fps).floor();
+    (f64::from(drift) * frame / fps).rem_euclid(SEED_DRIFT_WRAP) as f32
+}
+
+fn drifted_seed(seed: f32, drift_offset: f32, drift: f32, frames_ahead: f32, fps: f32) -> f32 {
+    seed + drift_offset + drift * frames_ahead / fps.max(0.0001)
+}
+
+fn smooth_turbulent_noise(
+    pos: Vec3,
+    time_q: f32,
+    frequency: f32,
+    seed: f32,
+    kind: NoiseKind,
+) -> Vec3 {
+    let p = pos * frequency + seed;
+    match kind {
+        NoiseKind::Curl => curl_noise(p, time_q),
+        _ => turbulent_field(p, time_q, kind),
+    }
+}
+
+fn turbulent_field(p: Vec3, time_q: f32, kind: NoiseKind) -> Vec3 {
+    let t = time_q * AXIS_TIME_SCALES;
+
+    Vec3::new(
+        boil_noise_3d(p + AXIS_OFFSETS[0] + Vec3::new(t.x, 0.0, 0.0), kind),
+        boil_noise_3d(p + AXIS_OFFSETS[1] + Vec3::new(0.0, t.y, 0.0), kind),
+        boil_noise_3d(p + AXIS_OFFSETS[2] + Vec3::new(0.0, 0.0, t.z), kind),
+    )
+}
+
+fn curl_noise(p: Vec3, time_q: f32) -> Vec3 {
+    let field = |p| turbulent_field(p, time_q, NoiseKind::Curl);
+    let f = field(p);
+    let dx = (field(p + Vec3::X * CURL_STEP) - f) / CURL_STEP;
+    let dy = (field(p + Vec3::Y * CURL_STEP) - f) / CURL_STEP;
+    let dz = (field(p + Vec3::Z * CURL_STEP) - f) / CURL_STEP;
+    let curl = Vec3::new(dy.z - dz.y, dz.x - dx.z, dx.y - dy.x);
+    (curl * CURL_SCALE).clamp(Vec3::splat(-1.0), Vec3::ONE)
+}
+
+// WGSL `fract` is `x - floor(x)`, which differs from `f32::fract` for negative inputs.
+fn fract(v: Vec3) -> Vec3 {
+    v - v.floor()
+}
+
+// WGSL `step(edge, x)` is 1 where `edge <= x`.
+fn step(edge: Vec3, x: Vec3) -> Vec3 {
+    Vec3::select(edge.cmple(x), Vec3::ONE, Vec3::ZERO)
+}
+
+fn fract_f32(x: f32) -> f32 {
+    x - x.floor()
+}
+
+fn mix(a: f32, b: f32, t: f32) -> f32 {
+    a * (1.0 - t) + b * t
+}
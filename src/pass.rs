@@ -0,0 +1,166 @@
+//! Isolated silhouette pass for compositing the boiled geometry in post.
+
+use bevy::{
+    camera::{RenderTarget, visibility::RenderLayers},
+    image::BevyDefault,
+    mesh::{MeshTag, skinning::SkinnedMesh},
+    pbr::ExtendedMaterial,
+    prelude::*,
+    render::render_resource::TextureFormat,
+};
+
+use crate::{LineBoilApplied, LineBoilBaseMaterial, LineBoilMaterial, LineBoilViewCopy};
+
+/// Renders a flat, boiled copy of an entity's meshes onto a dedicated render layer.
+///
+/// Add this next to [`LineBoil`](crate::LineBoil) on a root entity. Every converted mesh
+/// in its hierarchy gets an unlit child copy on [`layer`](Self::layer) that uses the same
+/// displacement as the visible mesh, so a camera rendering only that layer captures the
+/// boiled silhouette in isolation. Use [`LineBoilPass::camera`] to spawn such a camera
+/// and [`LineBoilPass::target_image`] to create the texture it renders into.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let pass = LineBoilPass::new(1);
+/// let target = images.add(LineBoilPass::target_image(1280, 720));
+///
+/// commands.spawn((
+///     SceneRoot(asset_server.load("character.glb#Scene0")),
+///     LineBoil::subtle(),
+///     pass.clone(),
+/// ));
+///
+/// // Parent the pass camera to the main camera so both views line up.
+/// commands.entity(main_camera).with_child(pass.camera(target.clone()));
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct LineBoilPass {
+    /// Render layer the silhouette copies are placed on.
+    pub layer: usize,
+    /// Flat color the silhouette copies are drawn with.
+    pub color: Color,
+}
+
+impl Default for LineBoilPass {
+    fn default() -> Self {
+        Self::new(1)
+    }
+}
+
+impl LineBoilPass {
+    /// Creates a pass rendering white silhouettes onto `layer`.
+    pub fn new(layer: usize) -> Self {
+        Self {
+            layer,
+            color: Color::WHITE,
+        }
+    }
+
+    /// Sets the flat color the silhouettes are drawn with.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns the [`RenderLayers`] the silhouette copies are placed on.
+    pub fn render_layers(&self) -> RenderLayers {
+        RenderLayers::layer(self.layer)
+    }
+
+    /// Creates an image suitable as the render target of the pass camera.
+    pub fn target_image(width: u32, height: u32) -> Image {
+        Image::new_target_texture(width, height, TextureFormat::bevy_default(), None)
+    }
+
+    /// Returns a camera bundle that renders only this pass into `target`.
+    ///
+    /// The camera clears to transparent and renders before the main camera.
+    pub fn camera(&self, target: Handle<Image>) -> impl Bundle {
+        (
+            Camera3d::default(),
+            Camera {
+                order: -1,
+                clear_color: ClearColorConfig::Custom(Color::NONE),
+                ..default()
+            },
+            RenderTarget::Image(target.into()),
+            self.render_layers(),
+        )
+    }
+}
+
+/// Marker component for the silhouette copies spawned by [`LineBoilPass`].
+#[derive(Component)]
+pub struct LineBoilPassCopy;
+
+/// Spawns silhouette copies for newly converted meshes under a [`LineBoilPass`] root.
+///
+/// Copies are always flat, unlit `StandardMaterial`s, whatever the source's base material.
+#[allow(clippy::type_complexity)]
+pub(crate) fn spawn_line_boil_pass_copies<M: LineBoilBaseMaterial>(
+    mut commands: Commands,
+    pass_query: Query<&LineBoilPass>,
+    parent_query: Query<&ChildOf>,
+    mesh_query: Query<
+        (
+            Entity,
+            &LineBoilApplied,
+            &Mesh3d,
+            &MeshMaterial3d<ExtendedMaterial<M, LineBoilMaterial>>,
+            Option<&SkinnedMesh>,
+            Option<&MeshTag>,
+        ),
+        (
+            Added<LineBoilApplied>,
+            Without<LineBoilPassCopy>,
+            Without<LineBoilViewCopy>,
+        ),
+    >,
+    // Both resources are the same one when `M` is `StandardMaterial`.
+    mut line_boil_materials: ParamSet<(
+        Res<Assets<ExtendedMaterial<M, LineBoilMaterial>>>,
+        ResMut<Assets<ExtendedMaterial<StandardMaterial, LineBoilMaterial>>>,
+    )>,
+) {
+    for (entity, applied, mesh, mat_handle, skinned_mesh, mesh_tag) in mesh_query.iter() {
+        let Some(pass) = std::iter::once(entity)
+            .chain(parent_query.iter_ancestors(entity))
+            .find_map(|ancestor| pass_query.get(ancestor).ok())
+        else {
+            continue;
+        };
+        let Some(extension) = line_boil_materials
+            .p0()
+            .get(&mat_handle.0)
+            .map(|material| material.extension.clone())
+        else {
+            continue;
+        };
+
+        let flat_handle = line_boil_materials.p1().add(ExtendedMaterial {
+            base: StandardMaterial {
+                base_color: pass.color,
+                unlit: true,
+                ..default()
+            },
+            extension: extension.on_base::<StandardMaterial>(),
+        });
+
+        // The copy shares the source mesh's root so it boils in lockstep with it.
+        let mut copy = commands.spawn((
+            LineBoilPassCopy,
+            LineBoilApplied { root: applied.root },
+            Mesh3d(mesh.0.clone()),
+            MeshMaterial3d(flat_handle),
+            pass.render_layers(),
+            ChildOf(entity),
+        ));
+        if let Some(skinned_mesh) = skinned_mesh {
+            copy.insert(skinned_mesh.clone());
+        }
+        if let Some(mesh_tag) = mesh_tag {
+            copy.insert(mesh_tag.clone());
+        }
+    }
+}
@@ -0,0 +1,472 @@
+//! GPU readback of the displaced vertex positions.
+
+use bevy::{
+    asset::RenderAssetUsages,
+    ecs::system::SystemParam,
+    mesh::{MeshTag, MeshVertexAttributeId, VertexAttributeValues},
+    pbr::ExtendedMaterial,
+    prelude::*,
+    render::{
+        Render, RenderApp, RenderStartup, RenderSystems,
+        extract_resource::{ExtractResource, ExtractResourcePlugin},
+        gpu_readback::{Readback, ReadbackComplete},
+        render_asset::RenderAssets,
+        render_graph::{self, RenderGraph, RenderLabel},
+        render_resource::{
+            BindGroup, BindGroupEntries, BindGroupLayoutDescriptor, BindGroupLayoutEntries,
+            BufferUsages, CachedComputePipelineId, ComputePassDescriptor,
+            ComputePipelineDescriptor, PipelineCache, ShaderSize, ShaderStages, ShaderType,
+            SpecializedComputePipeline, SpecializedComputePipelines,
+            binding_types::{storage_buffer, storage_buffer_read_only, uniform_buffer},
+            encase::{UniformBuffer, internal::WriteInto},
+        },
+        renderer::{RenderContext, RenderDevice},
+        storage::{GpuShaderStorageBuffer, ShaderStorageBuffer},
+    },
+    shader::ShaderDefVal,
+};
+
+use crate::{
+    ATTRIBUTE_BOIL_AREA, ATTRIBUTE_BOIL_CREASE, LINE_BOIL_READBACK_SHADER_HANDLE,
+    LineBoilApplied, LineBoilBaseMaterial, LineBoilMaterial, LineBoilMaterialKey,
+    LineBoilSystems, material::LineBoilUniform,
+};
+
+/// Must match `INSTANCE_SEED_WRAP` in `line_boil_common.wgsl`.
+const INSTANCE_SEED_WRAP: u32 = 4096;
+
+/// Must match `@workgroup_size` in `line_boil_readback.wgsl`.
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Requests the displaced positions of a boiled mesh, as seen by a camera.
+///
+/// Spawn it on its own entity and observe [`LineBoilReadbackComplete`] on that entity. The
+/// displacement of the current frame is run on the GPU by a compute shader sharing the
+/// vertex shader's code, so the result matches what is drawn, e.g. for precise hit tests
+/// against the boiled silhouette or for baking the boil out to a mesh. The result arrives a
+/// few frames later, after which the request entity is despawned.
+///
+/// The positions are in world space, one per vertex of the mesh in its vertex order. They are
+/// computed from the mesh's rest pose: skinning and morph targets are not applied.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// commands
+///     .spawn(LineBoilReadback::new(sword_mesh, camera))
+///     .observe(|readback: On<LineBoilReadbackComplete>| {
+///         info!("first vertex drawn at {}", readback.positions[0]);
+///     });
+/// ```
+#[derive(Component, Clone, Copy, Debug)]
+pub struct LineBoilReadback {
+    /// Converted mesh entity whose vertices are displaced.
+    pub mesh: Entity,
+    /// Camera the screen-space displacement is computed for.
+    pub camera: Entity,
+}
+
+impl LineBoilReadback {
+    /// Creates a readback of `mesh` as displaced for `camera`.
+    pub fn new(mesh: Entity, camera: Entity) -> Self {
+        Self { mesh, camera }
+    }
+}
+
+/// Triggered on a [`LineBoilReadback`] entity with the displaced positions.
+#[derive(EntityEvent, Clone, Debug)]
+pub struct LineBoilReadbackComplete {
+    /// The [`LineBoilReadback`] entity.
+    pub entity: Entity,
+    /// Displaced world-space position of every vertex, in the mesh's vertex order.
+    pub positions: Vec<Vec3>,
+}
+
+/// GPU layout of the per-request parameters. Must match `ReadbackParams` in
+/// `line_boil_readback.wgsl`.
+#[derive(ShaderType)]
+struct ReadbackParams {
+    world_from_local: Mat4,
+    clip_from_world: Mat4,
+    world_from_clip: Mat4,
+    vertex_count: u32,
+    instance_seed: f32,
+}
+
+/// GPU layout of one input vertex. Must match `ReadbackVertex` in `line_boil_readback.wgsl`.
+#[derive(ShaderType, Clone, Copy)]
+struct ReadbackVertex {
+    /// Local position, with the boil area in `w`.
+    position: Vec4,
+    /// Local normal, with the boil crease in `w`.
+    normal: Vec4,
+    /// Mask weight in `x`.
+    mask: Vec4,
+}
+
+/// Pipeline key of the readback compute shader.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct ReadbackPipelineKey {
+    material: LineBoilMaterialKey,
+    has_area: bool,
+    has_crease: bool,
+}
+
+/// A started readback, dispatched every frame until its result arrives.
+#[derive(Clone)]
+struct ReadbackJob {
+    request: Entity,
+    key: ReadbackPipelineKey,
+    vertex_count: u32,
+    params: Handle<ShaderStorageBuffer>,
+    settings: Handle<ShaderStorageBuffer>,
+    vertices: Handle<ShaderStorageBuffer>,
+    displaced: Handle<ShaderStorageBuffer>,
+}
+
+#[derive(Resource, ExtractResource, Clone, Default)]
+pub(crate) struct LineBoilReadbackJobs(Vec<ReadbackJob>);
+
+/// Adds [`LineBoilReadback`] support. Added by [`LineBoilPlugin`](crate::LineBoilPlugin).
+pub(crate) struct LineBoilReadbackPlugin;
+
+impl Plugin for LineBoilReadbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LineBoilReadbackJobs>()
+            .add_plugins(ExtractResourcePlugin::<LineBoilReadbackJobs>::default())
+            .add_systems(
+                Update,
+                reject_line_boil_readbacks.before(LineBoilSystems::Upload),
+            );
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .init_resource::<SpecializedComputePipelines<LineBoilReadbackPipeline>>()
+            .add_systems(
+                RenderStartup,
+                (init_line_boil_readback_pipeline, add_line_boil_readback_node),
+            )
+            .add_systems(
+                Render,
+                prepare_line_boil_readback_bind_groups.in_set(RenderSystems::PrepareBindGroups),
+            );
+    }
+}
+
+/// Despawns new [`LineBoilReadback`]s whose mesh or camera is unusable, and drops the work
+/// of requests despawned before their result arrived.
+fn reject_line_boil_readbacks(
+    mut commands: Commands,
+    request_query: Query<(Entity, &LineBoilReadback), Without<Readback>>,
+    live_query: Query<(), With<LineBoilReadback>>,
+    mesh_query: Query<(), With<LineBoilApplied>>,
+    camera_query: Query<(), With<Camera>>,
+    mut jobs: ResMut<LineBoilReadbackJobs>,
+) {
+    jobs.0.retain(|job| live_query.contains(job.request));
+
+    for (request, readback) in request_query.iter() {
+        if !mesh_query.contains(readback.mesh) {
+            warn!("LineBoilReadback: {} is not a line boil mesh", readback.mesh);
+            commands.entity(request).despawn();
+        } else if !camera_query.contains(readback.camera) {
+            warn!("LineBoilReadback: {} is not a camera", readback.camera);
+            commands.entity(request).despawn();
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+#[derive(SystemParam)]
+pub(crate) struct ReadbackMeshes<'w, 's, B: LineBoilBaseMaterial> {
+    mesh_query: Query<
+        'w,
+        's,
+        (
+            &'static Mesh3d,
+            &'static MeshMaterial3d<ExtendedMaterial<B, LineBoilMaterial>>,
+            &'static GlobalTransform,
+            Option<&'static MeshTag>,
+        ),
+    >,
+    camera_query: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+    meshes: Res<'w, Assets<Mesh>>,
+    line_boil_materials: Res<'w, Assets<ExtendedMaterial<B, LineBoilMaterial>>>,
+}
+
+/// Snapshots the mesh, material and camera of new [`LineBoilReadback`]s of meshes with a
+/// `B`-based material and starts their GPU work.
+pub(crate) fn start_line_boil_readbacks<B: LineBoilBaseMaterial>(
+    mut commands: Commands,
+    request_query: Query<(Entity, &LineBoilReadback), Without<Readback>>,
+    sources: ReadbackMeshes<B>,
+    mut buffers: ResMut<Assets<ShaderStorageBuffer>>,
+    mut jobs: ResMut<LineBoilReadbackJobs>,
+) {
+    for (request, readback) in request_query.iter() {
+        // Meshes with other base materials are handled by their own instance.
+        let (Ok((mesh_handle, mat_handle, transform, mesh_tag)), Ok((camera, camera_transform))) = (
+            sources.mesh_query.get(readback.mesh),
+            sources.camera_query.get(readback.camera),
+        ) else {
+            continue;
+        };
+        let (Some(mesh), Some(material)) = (
+            sources.meshes.get(&mesh_handle.0),
+            sources.line_boil_materials.get(&mat_handle.0),
+        ) else {
+            // Assets still loading; retry next frame.
+            continue;
+        };
+        let Ok(positions) = mesh.try_attribute_option(Mesh::ATTRIBUTE_POSITION) else {
+            warn!(
+                "LineBoilReadback: mesh of {} was unloaded from the main world",
+                readback.mesh
+            );
+            commands.entity(request).despawn();
+            continue;
+        };
+        let Some(positions) = positions.and_then(VertexAttributeValues::as_float3) else {
+            warn!("LineBoilReadback: mesh of {} has no positions", readback.mesh);
+            commands.entity(request).despawn();
+            continue;
+        };
+
+        let normals = mesh
+            .try_attribute_option(Mesh::ATTRIBUTE_NORMAL)
+            .ok()
+            .flatten()
+            .and_then(VertexAttributeValues::as_float3);
+        let areas = float_attribute(mesh, ATTRIBUTE_BOIL_AREA.id);
+        let creases = float_attribute(mesh, ATTRIBUTE_BOIL_CREASE.id);
+        let masks = material
+            .extension
+            .settings
+            .mask_attribute
+            .and_then(|mask| mask.weights(mesh));
+        let vertices: Vec<ReadbackVertex> = positions
+            .iter()
+            .enumerate()
+            .map(|(index, position)| ReadbackVertex {
+                position: Vec3::from(*position).extend(areas.map_or(0.0, |areas| areas[index])),
+                normal: normals
+                    .map_or(Vec3::ZERO, |normals| Vec3::from(normals[index]))
+                    .extend(creases.map_or(0.0, |creases| creases[index])),
+                mask: Vec4::X * masks.as_ref().map_or(1.0, |masks| masks[index]),
+            })
+            .collect();
+
+        let clip_from_world =
+            camera.clip_from_view() * camera_transform.to_matrix().inverse();
+        let vertex_count = vertices.len() as u32;
+        let params = ReadbackParams {
+            world_from_local: transform.to_matrix(),
+            clip_from_world,
+            world_from_clip: clip_from_world.inverse(),
+            vertex_count,
+            instance_seed: match mesh_tag {
+                Some(tag) if material.extension.settings.per_instance_seed => {
+                    (tag.0 % INSTANCE_SEED_WRAP) as f32
+                }
+                _ => 0.0,
+            },
+        };
+
+        let mut vertex_buffer = ShaderStorageBuffer::from(vertices);
+        vertex_buffer.asset_usage = RenderAssetUsages::RENDER_WORLD;
+        // The header and one position per vertex, see `line_boil_readback.wgsl`.
+        let mut displaced = ShaderStorageBuffer::with_size(
+            (vertex_count as usize + 1) * Vec4::SHADER_SIZE.get() as usize,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+        displaced.buffer_description.usage |= BufferUsages::COPY_SRC;
+        let displaced = buffers.add(displaced);
+
+        jobs.0.push(ReadbackJob {
+            request,
+            key: ReadbackPipelineKey {
+                material: (&material.extension).into(),
+                has_area: areas.is_some(),
+                has_crease: creases.is_some(),
+            },
+            vertex_count,
+            params: buffers.add(uniform_storage_buffer(&params)),
+            settings: buffers.add(uniform_storage_buffer(&LineBoilUniform::from(
+                &material.extension,
+            ))),
+            vertices: buffers.add(vertex_buffer),
+            displaced: displaced.clone(),
+        });
+        commands
+            .entity(request)
+            .insert(Readback::buffer(displaced))
+            .observe(finish_line_boil_readback);
+    }
+}
+
+/// Returns the values of a scalar `f32` vertex attribute.
+fn float_attribute(mesh: &Mesh, id: MeshVertexAttributeId) -> Option<&[f32]> {
+    match mesh.try_attribute_option(id).ok()?? {
+        VertexAttributeValues::Float32(values) => Some(values),
+        _ => None,
+    }
+}
+
+/// Encodes `value` with uniform layout into a buffer that can be bound as a uniform.
+fn uniform_storage_buffer<T: ShaderType + WriteInto>(value: &T) -> ShaderStorageBuffer {
+    let mut encoded = UniformBuffer::new(Vec::<u8>::new());
+    encoded.write(value).unwrap();
+    let mut buffer =
+        ShaderStorageBuffer::new(&encoded.into_inner(), RenderAssetUsages::RENDER_WORLD);
+    buffer.buffer_description.usage |= BufferUsages::UNIFORM;
+    buffer
+}
+
+/// Turns the read back buffer into [`LineBoilReadbackComplete`] once the compute shader has
+/// written it.
+fn finish_line_boil_readback(
+    readback: On<ReadbackComplete>,
+    mut commands: Commands,
+    mut jobs: ResMut<LineBoilReadbackJobs>,
+) {
+    let values: Vec<Vec4> = readback.to_shader_type();
+    // Zeroed until the compute pipeline has compiled and run.
+    let Some((header, positions)) = values.split_first() else {
+        return;
+    };
+    if header.x != 1.0 {
+        return;
+    }
+
+    let request = readback.entity;
+    jobs.0.retain(|job| job.request != request);
+    commands.trigger(LineBoilReadbackComplete {
+        entity: request,
+        positions: positions.iter().map(|position| position.truncate()).collect(),
+    });
+    commands.entity(request).despawn();
+}
+
+#[derive(Resource)]
+struct LineBoilReadbackPipeline {
+    layout: BindGroupLayoutDescriptor,
+}
+
+impl SpecializedComputePipeline for LineBoilReadbackPipeline {
+    type Key = ReadbackPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> ComputePipelineDescriptor {
+        let mut shader_defs: Vec<ShaderDefVal> = key
+            .material
+            .displacement_shader_defs(key.has_area, key.has_crease);
+        shader_defs.push(ShaderDefVal::UInt("MATERIAL_BIND_GROUP".into(), 0));
+        ComputePipelineDescriptor {
+            label: Some("line_boil_readback_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            shader: LINE_BOIL_READBACK_SHADER_HANDLE,
+            shader_defs,
+            ..default()
+        }
+    }
+}
+
+fn init_line_boil_readback_pipeline(mut commands: Commands) {
+    let layout = BindGroupLayoutDescriptor::new(
+        "line_boil_readback_layout",
+        &BindGroupLayoutEntries::with_indices(
+            ShaderStages::COMPUTE,
+            (
+                (0, uniform_buffer::<ReadbackParams>(false)),
+                (1, storage_buffer_read_only::<Vec<ReadbackVertex>>(false)),
+                (2, storage_buffer::<Vec<Vec4>>(false)),
+                (100, uniform_buffer::<LineBoilUniform>(false)),
+            ),
+        ),
+    );
+    commands.insert_resource(LineBoilReadbackPipeline { layout });
+}
+
+/// Compute work of the readbacks whose buffers are on the GPU this frame.
+#[derive(Resource, Default)]
+struct LineBoilReadbackDispatches(Vec<(CachedComputePipelineId, BindGroup, u32)>);
+
+fn prepare_line_boil_readback_bind_groups(
+    mut commands: Commands,
+    jobs: Res<LineBoilReadbackJobs>,
+    pipeline: Res<LineBoilReadbackPipeline>,
+    mut pipelines: ResMut<SpecializedComputePipelines<LineBoilReadbackPipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    render_device: Res<RenderDevice>,
+    buffers: Res<RenderAssets<GpuShaderStorageBuffer>>,
+) {
+    let layout = pipeline_cache.get_bind_group_layout(&pipeline.layout);
+    let mut dispatches = Vec::new();
+    for job in &jobs.0 {
+        let (Some(params), Some(settings), Some(vertices), Some(displaced)) = (
+            buffers.get(&job.params),
+            buffers.get(&job.settings),
+            buffers.get(&job.vertices),
+            buffers.get(&job.displaced),
+        ) else {
+            continue;
+        };
+        let bind_group = render_device.create_bind_group(
+            "line_boil_readback_bind_group",
+            &layout,
+            &BindGroupEntries::with_indices((
+                (0, params.buffer.as_entire_binding()),
+                (1, vertices.buffer.as_entire_binding()),
+                (2, displaced.buffer.as_entire_binding()),
+                (100, settings.buffer.as_entire_binding()),
+            )),
+        );
+        let pipeline_id = pipelines.specialize(&pipeline_cache, &pipeline, job.key);
+        // The header is written by invocation 0, so dispatch at least one workgroup.
+        let workgroups = (job.vertex_count + 1).div_ceil(WORKGROUP_SIZE);
+        dispatches.push((pipeline_id, bind_group, workgroups));
+    }
+    commands.insert_resource(LineBoilReadbackDispatches(dispatches));
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct LineBoilReadbackLabel;
+
+fn add_line_boil_readback_node(mut render_graph: ResMut<RenderGraph>) {
+    // A top-level node runs once per frame, independently of the cameras.
+    render_graph.add_node(LineBoilReadbackLabel, LineBoilReadbackNode);
+}
+
+struct LineBoilReadbackNode;
+
+impl render_graph::Node for LineBoilReadbackNode {
+    fn run(
+        &self,
+        _graph: &mut render_graph::RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), render_graph::NodeRunError> {
+        let Some(dispatches) = world.get_resource::<LineBoilReadbackDispatches>() else {
+            return Ok(());
+        };
+        let pipeline_cache = world.resource::<PipelineCache>();
+        for (pipeline_id, bind_group, workgroups) in &dispatches.0 {
+            // Not compiled yet; the header stays zeroed and the readback waits.
+            let Some(pipeline) = pipeline_cache.get_compute_pipeline(*pipeline_id) else {
+                continue;
+            };
+            let mut pass =
+                render_context
+                    .command_encoder()
+                    .begin_compute_pass(&ComputePassDescriptor {
+                        label: Some("line_boil_readback"),
+                        ..default()
+                    });
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.set_pipeline(pipeline);
+            pass.dispatch_workgroups(*workgroups, 1, 1);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+//! Automatic line boil for scenes loaded from configured asset paths.
+
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+
+use crate::LineBoil;
+
+/// Rules that add [`LineBoil`] to scenes based on the asset path they were loaded from.
+///
+/// Insert this resource to stylize whole asset categories without per-spawn code.
+/// Each rule matches asset paths that start with the given prefix (compared per path
+/// component, relative to the asset folder). The first matching rule wins, and scene
+/// roots that already carry a [`LineBoil`] are left untouched.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// app.insert_resource(
+///     LineBoilSceneRules::default()
+///         .with_rule("characters", LineBoil::subtle())
+///         .with_rule("props/hand_drawn", LineBoil::aggressive()),
+/// );
+/// ```
+#[derive(Resource, Default, Clone)]
+pub struct LineBoilSceneRules {
+    rules: Vec<(PathBuf, LineBoil)>,
+}
+
+impl LineBoilSceneRules {
+    /// Adds a rule applying `line_boil` to scenes loaded from under `prefix`.
+    pub fn with_rule(mut self, prefix: impl Into<PathBuf>, line_boil: LineBoil) -> Self {
+        self.add_rule(prefix, line_boil);
+        self
+    }
+
+    /// Adds a rule applying `line_boil` to scenes loaded from under `prefix`.
+    pub fn add_rule(&mut self, prefix: impl Into<PathBuf>, line_boil: LineBoil) -> &mut Self {
+        self.rules.push((prefix.into(), line_boil));
+        self
+    }
+
+    /// Returns the [`LineBoil`] of the first rule matching `path`, if any.
+    pub fn matching(&self, path: &Path) -> Option<&LineBoil> {
+        self.rules
+            .iter()
+            .find(|(prefix, _)| path.starts_with(prefix))
+            .map(|(_, line_boil)| line_boil)
+    }
+}
+
+/// Inserts [`LineBoil`] on newly spawned scene roots whose asset path matches a rule.
+#[allow(clippy::type_complexity)]
+pub(crate) fn apply_line_boil_scene_rules(
+    mut commands: Commands,
+    rules: Res<LineBoilSceneRules>,
+    scene_query: Query<(Entity, &SceneRoot), (Added<SceneRoot>, Without<LineBoil>)>,
+) {
+    for (entity, scene_root) in scene_query.iter() {
+        let Some(asset_path) = scene_root.0.path() else {
+            continue;
+        };
+
+        if let Some(line_boil) = rules.matching(asset_path.path()) {
+            commands.entity(entity).insert(line_boil.clone());
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Cheaper boil variants for secondary cameras.
+
+use bevy::{
+    camera::visibility::RenderLayers,
+    mesh::{MeshTag, morph::MeshMorphWeights, skinning::SkinnedMesh},
+    pbr::ExtendedMaterial,
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use crate::{
+    LineBoilApplied, LineBoilBaseMaterial, LineBoilMaterial, LineBoilPassCopy, LineBoilSettings,
+};
+
+/// Renders the same objects with different boil settings for cameras on another render layer.
+///
+/// Add this next to [`LineBoil`](crate::LineBoil) on a root entity. Every converted mesh in
+/// its hierarchy gets a child copy on [`layer`](Self::layer) that boils with
+/// [`settings`](Self::settings) instead, e.g. a cheaper boil for a minimap. A camera
+/// rendering only that layer (plus whatever non-boiled content it should show) sees the
+/// variant, while cameras on the original layers keep the full-quality boil. Changing the
+/// component updates the existing copies, and adding it to an already converted root copies
+/// its meshes.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let line_boil = LineBoil::aggressive().with_mirror_axis(MirrorAxis::X);
+/// let minimap = LineBoilViewVariant::simplified(2, &line_boil.settings);
+///
+/// commands.spawn((SceneRoot(character), minimap.clone(), line_boil));
+/// commands.spawn((Camera3d::default(), minimap_camera, minimap.render_layers()));
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct LineBoilViewVariant {
+    /// Render layer the variant copies are placed on.
+    pub layer: usize,
+    /// Effect parameters of the variant copies.
+    pub settings: LineBoilSettings,
+}
+
+impl LineBoilViewVariant {
+    /// Creates a variant boiling with `settings` on `layer`.
+    pub fn new(layer: usize, settings: LineBoilSettings) -> Self {
+        Self { layer, settings }
+    }
+
+    /// Creates a variant that looks like `settings` but skips the per-vertex refinements
+    /// (area response, crease preservation, mirroring, detail normals, recomputed normals,
+    /// emissive pulse and shadow boil), for small or distant views where they aren't
+    /// visible.
+    pub fn simplified(layer: usize, settings: &LineBoilSettings) -> Self {
+        Self::new(
+            layer,
+            LineBoilSettings {
+                area_response: 0.0,
+                crease_preservation: 0.0,
+                mirror_axis: None,
+                detail_normal_strength: 0.0,
+                emissive_pulse: 0.0,
+                boil_shadows: false,
+                recompute_normals: false,
+                ..*settings
+            },
+        )
+    }
+
+    /// Returns the [`RenderLayers`] the variant copies are placed on.
+    pub fn render_layers(&self) -> RenderLayers {
+        RenderLayers::layer(self.layer)
+    }
+}
+
+/// Marker component for the copies spawned by [`LineBoilViewVariant`].
+#[derive(Component)]
+pub struct LineBoilViewCopy;
+
+/// Settings a [`LineBoilViewCopy`] boils with in place of its root's.
+#[derive(Component)]
+pub(crate) struct LineBoilViewCopySettings(pub LineBoilSettings);
+
+/// Variant materials shared by the copies of each [`LineBoilViewVariant`], keyed by the
+/// variant's entity and the source material, so copies of submeshes sharing a material share
+/// one too.
+type LineBoilVariantMaterials<M> = HashMap<
+    (Entity, AssetId<ExtendedMaterial<M, LineBoilMaterial>>),
+    Handle<ExtendedMaterial<M, LineBoilMaterial>>,
+>;
+
+/// Spawns variant copies for converted meshes under a [`LineBoilViewVariant`] root, whether
+/// the mesh or the variant is the newer one, and resyncs existing copies when the variant
+/// changes.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn spawn_line_boil_view_copies<M: LineBoilBaseMaterial>(
+    mut commands: Commands,
+    mut variant_materials: Local<LineBoilVariantMaterials<M>>,
+    variant_query: Query<Ref<LineBoilViewVariant>>,
+    added_query: Query<(), Added<LineBoilApplied>>,
+    parent_query: Query<&ChildOf>,
+    mesh_query: Query<
+        (
+            Entity,
+            Ref<LineBoilApplied>,
+            &Mesh3d,
+            &MeshMaterial3d<ExtendedMaterial<M, LineBoilMaterial>>,
+            Option<&SkinnedMesh>,
+            Option<&MeshTag>,
+            Option<&MeshMorphWeights>,
+            Option<&Children>,
+        ),
+        (Without<LineBoilPassCopy>, Without<LineBoilViewCopy>),
+    >,
+    mut copy_query: Query<
+        (
+            &mut LineBoilViewCopySettings,
+            &mut RenderLayers,
+            &mut MeshMaterial3d<ExtendedMaterial<M, LineBoilMaterial>>,
+        ),
+        With<LineBoilViewCopy>,
+    >,
+    mut line_boil_materials: ResMut<Assets<ExtendedMaterial<M, LineBoilMaterial>>>,
+) {
+    variant_materials.retain(|&(variant_entity, source), _| {
+        variant_query.contains(variant_entity) && line_boil_materials.contains(source)
+    });
+    if added_query.is_empty() && !variant_query.iter().any(|variant| variant.is_changed()) {
+        return;
+    }
+
+    for (entity, applied, mesh, mat_handle, skinned_mesh, mesh_tag, morph_weights, children) in
+        mesh_query.iter()
+    {
+        let Some((variant_entity, variant)) = std::iter::once(entity)
+            .chain(parent_query.iter_ancestors(entity))
+            .find_map(|ancestor| Some((ancestor, variant_query.get(ancestor).ok()?)))
+        else {
+            continue;
+        };
+        if !applied.is_added() && !variant.is_changed() {
+            continue;
+        }
+
+        let variant_handle = match variant_materials.get(&(variant_entity, mat_handle.id())) {
+            Some(handle) => handle.clone(),
+            None => {
+                let Some(base) = line_boil_materials
+                    .get(&mat_handle.0)
+                    .map(|material| material.base.clone())
+                else {
+                    continue;
+                };
+                let handle = line_boil_materials.add(ExtendedMaterial {
+                    base,
+                    extension: LineBoilMaterial::new(variant.settings).on_base::<M>(),
+                });
+                variant_materials.insert((variant_entity, mat_handle.id()), handle.clone());
+                handle
+            }
+        };
+        // Only touch the asset (and re-upload it) when the variant's settings changed.
+        if line_boil_materials
+            .get(&variant_handle)
+            .is_some_and(|material| material.extension.settings != variant.settings)
+            && let Some(material) = line_boil_materials.get_mut(&variant_handle)
+        {
+            material.extension.settings = variant.settings;
+        }
+
+        let existing_copy = children
+            .into_iter()
+            .flatten()
+            .copied()
+            .find(|&child| copy_query.contains(child));
+        if let Some(existing_copy) = existing_copy {
+            if let Ok((mut copy_settings, mut render_layers, mut copy_material)) =
+                copy_query.get_mut(existing_copy)
+            {
+                copy_settings.0 = variant.settings;
+                render_layers.set_if_neq(variant.render_layers());
+                if copy_material.0 != variant_handle {
+                    copy_material.0 = variant_handle;
+                }
+            }
+            continue;
+        }
+
+        // The copy shares the source mesh's root so drives and active windows still apply.
+        let mut copy = commands.spawn((
+            LineBoilViewCopy,
+            LineBoilViewCopySettings(variant.settings),
+            LineBoilApplied { root: applied.root },
+            Mesh3d(mesh.0.clone()),
+            MeshMaterial3d(variant_handle),
+            variant.render_layers(),
+            ChildOf(entity),
+        ));
+        if let Some(skinned_mesh) = skinned_mesh {
+            copy.insert(skinned_mesh.clone());
+        }
+        if let Some(mesh_tag) = mesh_tag {
+            copy.insert(mesh_tag.clone());
+        }
+        if let Some(morph_weights) = morph_weights {
+            copy.insert(morph_weights.clone());
+        }
+    }
+}
+
+/// Keeps the morph weights of view copies in step with their source meshes.
+///
+/// Bevy only passes animated weights down to the direct mesh children of a morph target
+/// node, which the copies (children of those meshes) are not.
+#[allow(clippy::type_complexity)]
+pub(crate) fn sync_line_boil_view_copy_weights(
+    source_query: Query<
+        (&MeshMorphWeights, &Children),
+        (Changed<MeshMorphWeights>, Without<LineBoilViewCopy>),
+    >,
+    mut copy_query: Query<&mut MeshMorphWeights, With<LineBoilViewCopy>>,
+) {
+    for (source_weights, children) in source_query.iter() {
+        let mut copies = copy_query.iter_many_mut(children);
+        while let Some(mut copy_weights) = copies.fetch_next() {
+            copy_weights.clear_weights();
+            copy_weights.extend_weights(source_weights.weights());
+        }
+    }
+}